@@ -20,12 +20,13 @@ use std::rc::Rc;
 
 use log::{error, info};
 
-use sulis_core::io::IO;
+use sulis_core::config::Config;
+use sulis_core::io::{InputRecorder, IO};
 use sulis_core::resource::ResourceSet;
 use sulis_core::ui::{self, Cursor};
 use sulis_core::util::{self, ActiveResources};
 use sulis_module::{Actor, Module};
-use sulis_state::{GameState, NextGameStep, SaveState};
+use sulis_state::{save_file, GameState, NewGameOptions, NextGameStep, SaveState};
 use sulis_view::{main_menu, RootView};
 
 fn init() -> Box<dyn IO> {
@@ -97,9 +98,13 @@ fn main_menu(io: &mut Box<dyn IO>) -> NextGameStep {
     }
 }
 
-fn new_campaign(io: &mut Box<dyn IO>, pc_actor: Rc<Actor>) -> NextGameStep {
+fn new_campaign(
+    io: &mut Box<dyn IO>,
+    pc_actor: Rc<Actor>,
+    options: NewGameOptions,
+) -> NextGameStep {
     info!("Initializing game state.");
-    if let Err(e) = GameState::init(pc_actor) {
+    if let Err(e) = GameState::init(pc_actor, options) {
         error!("{}", e);
         util::error_and_exit("There was a fatal error creating the game state.");
     };
@@ -122,11 +127,23 @@ fn run_campaign(io: &mut Box<dyn IO>) -> NextGameStep {
     let loop_updater = sulis_view::GameMainLoopUpdater::new(&view);
     let root = ui::create_ui_tree(view.clone());
 
+    if Config::debug().record_input && !InputRecorder::is_replaying() {
+        if let Err(e) = save_file::start_input_recording() {
+            error!("Unable to start input recording");
+            error!("{}", e);
+        }
+    }
+
     if let Err(e) = util::main_loop(io, root, Box::new(loop_updater)) {
         error!("{}", e);
         error!("Error in main loop.  Exiting...");
     }
 
+    if let Err(e) = save_file::finish_input_recording() {
+        error!("Unable to write input recording");
+        error!("{}", e);
+    }
+
     let mut view = view.borrow_mut();
     match view.next_step() {
         None => NextGameStep::Exit,
@@ -142,8 +159,23 @@ fn main() {
         use sulis_state::NextGameStep::*;
         next_step = match next_step {
             Exit => break,
-            NewCampaign { pc_actor } => new_campaign(&mut io, pc_actor),
+            NewCampaign { pc_actor, options } => new_campaign(&mut io, pc_actor, options),
             LoadCampaign { save_state } => load_campaign(&mut io, save_state),
+            LoadResourcesAndCampaign {
+                resources,
+                save_file,
+            } => {
+                resources.write();
+                load_resources();
+                match save_file::load_state(&save_file) {
+                    Ok(save_state) => load_campaign(&mut io, save_state),
+                    Err(e) => {
+                        error!("Error reading game state");
+                        error!("{}", e);
+                        main_menu(&mut io)
+                    }
+                }
+            }
             MainMenu => main_menu(&mut io),
             MainMenuReloadResources => {
                 load_resources();
@@ -152,12 +184,13 @@ fn main() {
             LoadModuleAndNewCampaign {
                 pc_actor,
                 module_dir,
+                options,
             } => {
                 let mut active = ActiveResources::read();
                 active.campaign = Some(module_dir);
                 active.write();
                 load_resources();
-                new_campaign(&mut io, pc_actor)
+                new_campaign(&mut io, pc_actor, options)
             }
             RecreateIO => {
                 io = create_io();
@@ -0,0 +1,62 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Writes the Lua script API reference, generated from the script bindings,
+//! as `lua_api.json`, `lua_api.md`, and `lua_api.html`.
+//!
+//! Usage: `sulis_api_docs [OUT_DIR]`
+//!
+//! The output directory defaults to `docs/api`.
+
+use std::env;
+use std::fs;
+use std::io::Error;
+use std::path::Path;
+use std::process;
+
+use sulis_core::resource::write_json_to_file;
+use sulis_state::script::ScriptApi;
+
+const DEFAULT_OUT_DIR: &str = "docs/api";
+
+fn write(dir: &Path) -> Result<(), Error> {
+    let api = ScriptApi::new();
+
+    fs::create_dir_all(dir)?;
+    write_json_to_file(dir.join("lua_api.json"), &api)?;
+    fs::write(dir.join("lua_api.md"), api.to_markdown())?;
+    fs::write(dir.join("lua_api.html"), api.to_html())?;
+
+    let methods: usize = api.types.iter().map(|t| t.methods.len()).sum();
+    println!(
+        "Wrote {} types with {} methods to {:?}",
+        api.types.len(),
+        methods,
+        dir
+    );
+    Ok(())
+}
+
+fn main() {
+    let dir = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_OUT_DIR.to_string());
+
+    if let Err(e) = write(Path::new(&dir)) {
+        eprintln!("Unable to write API reference to '{}': {}", dir, e);
+        process::exit(1);
+    }
+}
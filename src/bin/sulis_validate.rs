@@ -0,0 +1,123 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Loads a module and its mods without creating a window, and reports any
+//! problems found.  Exits with a non-zero status if there were any.
+//!
+//! Usage: `sulis_validate [MODULE_DIR] [MOD_DIR]...`
+//!
+//! If no module directory is specified, the currently active module and mods
+//! are validated.  Along with the module's YAML, each Lua script is checked
+//! for syntax errors and calls to methods not in the script API.
+
+use std::env;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use sulis_core::config::Config;
+use sulis_core::resource::ResourceSet;
+use sulis_core::util::ActiveResources;
+use sulis_module::{validator, Module};
+use sulis_state::script::ScriptLint;
+
+/// Prints all warnings and errors logged while loading, keeping a count
+/// of them
+struct ValidateLogger {
+    count: AtomicUsize,
+}
+
+impl Log for ValidateLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        self.count.fetch_add(1, Ordering::SeqCst);
+        eprintln!("{}: {}", record.level(), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: ValidateLogger = ValidateLogger {
+    count: AtomicUsize::new(0),
+};
+
+fn directories() -> Vec<String> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        return ActiveResources::read().directories();
+    }
+
+    let mut dirs = vec![Config::resources_config().directory];
+    dirs.extend(args);
+    dirs
+}
+
+fn main() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Warn);
+    }
+
+    let dirs = directories();
+    if dirs.len() < 2 {
+        eprintln!("No module specified and no module is currently active.");
+        process::exit(2);
+    }
+
+    println!("Validating '{:?}'", dirs);
+
+    let yaml = match ResourceSet::load_resources(dirs.clone()) {
+        Err(e) => {
+            eprintln!("Fatal error reading resources: {}", e);
+            process::exit(1);
+        }
+        Ok(yaml) => yaml,
+    };
+
+    let resources = yaml.resources.clone();
+    if let Err(e) = Module::load_resources(yaml, dirs) {
+        eprintln!("Fatal error loading module: {}", e);
+        process::exit(1);
+    }
+
+    let lint = ScriptLint::new();
+    let mut errors = lint.errors().to_vec();
+    errors.extend(validator::validate(&resources, &|id, func| {
+        lint.has_func(id, func)
+    }));
+
+    for error in errors.iter() {
+        println!("{}", error);
+    }
+
+    let warnings = LOGGER.count.load(Ordering::SeqCst);
+    println!(
+        "Found {} problems and {} load warnings",
+        errors.len(),
+        warnings
+    );
+
+    if !errors.is_empty() || warnings > 0 {
+        process::exit(1);
+    }
+}
@@ -48,6 +48,9 @@ pub struct Config {
     pub logging: LoggingConfig,
     pub editor: EditorConfig,
 
+    #[serde(default)]
+    pub saves: SaveConfig,
+
     #[serde(default)]
     pub debug: DebugConfig,
 }
@@ -125,6 +128,10 @@ impl Config {
         CONFIG.with(|c| c.borrow().logging.clone())
     }
 
+    pub fn save_config() -> SaveConfig {
+        CONFIG.with(|c| c.borrow().saves.clone())
+    }
+
     pub fn debug() -> DebugConfig {
         CONFIG.with(|c| c.borrow().debug.clone())
     }
@@ -154,11 +161,59 @@ impl Config {
     }
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct SaveConfig {
+    /// If true, new saves are written as compressed binary files rather
+    /// than JSON.  Both kinds of save may always be loaded.
+    pub compress: bool,
+
+    /// The number of autosave slots, which are overwritten oldest first.
+    /// Autosaving is disabled if this is zero.
+    pub autosave_slots: u32,
+
+    /// Whether to autosave after each transition to a different area
+    pub autosave_on_transition: bool,
+
+    /// Whether to autosave after the party rests
+    pub autosave_on_rest: bool,
+
+    /// Minutes of play between autosaves, or zero to disable timed autosaves
+    pub autosave_interval_minutes: u32,
+}
+
+impl Default for SaveConfig {
+    fn default() -> Self {
+        SaveConfig {
+            compress: false,
+            autosave_slots: 3,
+            autosave_on_transition: true,
+            autosave_on_rest: true,
+            autosave_interval_minutes: 10,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct DebugConfig {
     pub encounter_spawning: bool,
     pub limit_line_of_sight: bool,
+
+    /// If true, a save is created whenever a campaign is started or loaded,
+    /// and all input is recorded to a replay file alongside it
+    #[serde(default)]
+    pub record_input: bool,
+
+    /// If true, loading a save that has a replay file alongside it
+    /// plays back the recorded input
+    #[serde(default)]
+    pub replay_input: bool,
+
+    /// If true, the `ReloadScripts` and `ReloadResources` keybindings re-read
+    /// changed script and YAML files while playing
+    #[serde(default)]
+    pub hot_reload: bool,
 }
 
 impl Default for DebugConfig {
@@ -166,6 +221,9 @@ impl Default for DebugConfig {
         DebugConfig {
             encounter_spawning: true,
             limit_line_of_sight: true,
+            record_input: false,
+            replay_input: false,
+            hot_reload: false,
         }
     }
 }
@@ -269,6 +327,7 @@ pub struct InputConfig {
 pub enum IOAdapter {
     Auto,
     Glium,
+    Headless,
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -19,9 +19,21 @@ pub use self::event::Event;
 
 mod glium_adapter;
 
+pub mod headless_adapter;
+pub use self::headless_adapter::HeadlessDisplay;
+
+pub mod software_renderer;
+pub use self::software_renderer::SoftwareRenderer;
+
+pub mod thumbnail;
+pub use self::thumbnail::Thumbnail;
+
 mod input_action;
 pub use self::input_action::InputAction;
 
+pub mod input_recorder;
+pub use self::input_recorder::{InputRecorder, InputRecording};
+
 pub mod keyboard_event;
 pub use self::keyboard_event::KeyboardEvent;
 
@@ -58,6 +70,10 @@ pub trait IO {
     /// name is in the overall vec, with each entry in the vec for that key
     /// a valid (x, y) display resolution
     fn get_display_configurations(&self) -> Vec<DisplayConfiguration>;
+
+    /// Returns true if this IO has finished and the main loop should
+    /// exit, regardless of the state of the UI
+    fn is_exit(&self) -> bool;
 }
 
 pub trait GraphicsRenderer {
@@ -319,6 +335,7 @@ pub fn create() -> Result<Box<dyn IO>, Error> {
     match Config::display_adapter() {
         IOAdapter::Auto => get_auto_adapter(),
         IOAdapter::Glium => get_glium_adapter(),
+        IOAdapter::Headless => get_headless_adapter(),
     }
 }
 
@@ -333,6 +350,10 @@ pub fn get_glium_adapter() -> Result<Box<dyn IO>, Error> {
     Ok(Box::new(adapter))
 }
 
+pub fn get_headless_adapter() -> Result<Box<dyn IO>, Error> {
+    Ok(Box::new(HeadlessDisplay::new()))
+}
+
 #[cfg(target_os = "windows")]
 pub fn get_auto_adapter() -> Result<Box<dyn IO>, Error> {
     get_glium_adapter()
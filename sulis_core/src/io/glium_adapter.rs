@@ -20,6 +20,7 @@ use std::io::{Error, ErrorKind};
 use std::rc::Rc;
 
 use crate::config::{Config, DisplayMode};
+use crate::extern_image::imageops;
 use crate::io::event::ClickKind;
 use crate::io::keyboard_event::Key;
 use crate::io::*;
@@ -443,6 +444,23 @@ impl GliumDisplay {
             hidpi_factor,
         })
     }
+
+    fn capture_thumbnail(&self) {
+        let raw: RawImage2d<u8> = match self.display.read_front_buffer() {
+            Ok(raw) => raw,
+            Err(e) => {
+                warn!("Unable to read display for thumbnail: {:?}", e);
+                return;
+            }
+        };
+
+        let (width, height) = (raw.width, raw.height);
+        match ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(width, height, raw.data.into_owned()) {
+            None => warn!("Invalid display image for thumbnail"),
+            // the front buffer is read from the bottom row up
+            Some(image) => Thumbnail::capture(&imageops::flip_vertical(&image)),
+        }
+    }
 }
 
 const RESOLUTIONS: [(u32, u32); 8] = [
@@ -540,6 +558,14 @@ impl IO for GliumDisplay {
             Cursor::draw(&mut renderer, millis);
         }
         target.finish().unwrap();
+
+        if Thumbnail::is_requested() {
+            self.capture_thumbnail();
+        }
+    }
+
+    fn is_exit(&self) -> bool {
+        false
     }
 }
 
@@ -0,0 +1,332 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::{Ref, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::io::software_renderer::SoftwareRenderer;
+use crate::io::*;
+use crate::resource::ResourceSet;
+use crate::ui::{Cursor, Widget};
+use crate::util::Point;
+
+/// A single draw call recorded by the `HeadlessRenderer`, along with the
+/// scissor region that was active when it was made
+#[derive(Debug, Clone)]
+pub struct HeadlessDraw {
+    pub draw_list: DrawList,
+    pub scissor: Option<(Point, Size)>,
+}
+
+/// A texture registered with the headless renderer.  The image is the
+/// base image passed to `register_texture`, while `draws` holds everything
+/// that has been drawn onto it with `draw_to_texture` since it was last cleared
+pub struct HeadlessTexture {
+    pub image: ImageBuffer<Rgba<u8>, Vec<u8>>,
+    pub min_filter: TextureMinFilter,
+    pub mag_filter: TextureMagFilter,
+    pub draws: Vec<HeadlessDraw>,
+}
+
+/// The in memory output of a `HeadlessDisplay`.  This is shared via
+/// `HeadlessDisplay::output` so it may still be inspected after the display
+/// has been boxed and handed to the main loop
+#[derive(Default)]
+pub struct HeadlessOutput {
+    /// The number of frames that have been rendered so far
+    pub frames: u32,
+
+    /// All draw calls made to the screen during the most recently rendered frame
+    pub draws: Vec<HeadlessDraw>,
+
+    /// All textures that have been registered or lazily created, keyed by ID
+    pub textures: HashMap<String, HeadlessTexture>,
+
+    /// If enabled, all draw calls are also composited into an image by
+    /// this renderer.  See `HeadlessDisplay::enable_rasterizer`
+    pub rasterizer: Option<SoftwareRenderer>,
+}
+
+pub struct HeadlessRenderer<'a> {
+    output: &'a mut HeadlessOutput,
+    scissor: Option<(Point, Size)>,
+}
+
+impl<'a> HeadlessRenderer<'a> {
+    pub fn new(output: &'a mut HeadlessOutput) -> HeadlessRenderer<'a> {
+        HeadlessRenderer {
+            output,
+            scissor: None,
+        }
+    }
+
+    fn create_texture_if_missing(&mut self, texture_id: &str, draw_list: &DrawList) {
+        if self.output.textures.contains_key(texture_id) {
+            return;
+        }
+
+        trace!(
+            "Creating headless texture for ID '{}' of type '{:?}'",
+            texture_id,
+            draw_list.kind
+        );
+        let image = match draw_list.kind {
+            DrawListKind::Sprite => ResourceSet::spritesheet(texture_id).map(|s| s.image.clone()),
+            DrawListKind::Font => ResourceSet::font(texture_id).map(|f| f.image.clone()),
+        };
+
+        let image = match image {
+            None => {
+                warn!("Unable to find image for texture '{}'", texture_id);
+                return;
+            }
+            Some(image) => image,
+        };
+
+        self.register_texture(
+            texture_id,
+            image,
+            draw_list.texture_min_filter,
+            draw_list.texture_mag_filter,
+        );
+    }
+
+    fn record(&self, draw_list: DrawList) -> HeadlessDraw {
+        HeadlessDraw {
+            draw_list,
+            scissor: self.scissor,
+        }
+    }
+}
+
+impl<'a> GraphicsRenderer for HeadlessRenderer<'a> {
+    fn draw(&mut self, draw_list: DrawList) {
+        if draw_list.texture.is_empty() {
+            return;
+        }
+        self.create_texture_if_missing(&draw_list.texture, &draw_list);
+
+        if let Some(ref mut rasterizer) = self.output.rasterizer {
+            rasterizer.draw(draw_list.clone());
+        }
+
+        let draw = self.record(draw_list);
+        self.output.draws.push(draw);
+    }
+
+    fn draw_to_texture(&mut self, texture_id: &str, draw_list: DrawList) {
+        self.create_texture_if_missing(&draw_list.texture, &draw_list);
+
+        if let Some(ref mut rasterizer) = self.output.rasterizer {
+            rasterizer.draw_to_texture(texture_id, draw_list.clone());
+        }
+
+        let draw = self.record(draw_list);
+        match self.output.textures.get_mut(texture_id) {
+            None => warn!("Attempted to draw to invalid texture '{}'", texture_id),
+            Some(texture) => texture.draws.push(draw),
+        }
+    }
+
+    fn register_texture(
+        &mut self,
+        id: &str,
+        image: ImageBuffer<Rgba<u8>, Vec<u8>>,
+        min_filter: TextureMinFilter,
+        mag_filter: TextureMagFilter,
+    ) {
+        let dims = image.dimensions();
+        trace!("Registering headless texture '{}', {}x{}", id, dims.0, dims.1);
+        if let Some(ref mut rasterizer) = self.output.rasterizer {
+            rasterizer.register_texture(id, image.clone(), min_filter, mag_filter);
+        }
+
+        self.output.textures.insert(
+            id.to_string(),
+            HeadlessTexture {
+                image,
+                min_filter,
+                mag_filter,
+                draws: Vec::new(),
+            },
+        );
+    }
+
+    fn clear_texture(&mut self, id: &str) {
+        if let Some(ref mut rasterizer) = self.output.rasterizer {
+            rasterizer.clear_texture(id);
+        }
+
+        if let Some(texture) = self.output.textures.get_mut(id) {
+            for pixel in texture.image.pixels_mut() {
+                *pixel = Rgba([0, 0, 0, 0]);
+            }
+            texture.draws.clear();
+        }
+    }
+
+    fn clear_texture_region(&mut self, id: &str, min_x: i32, min_y: i32, max_x: i32, max_y: i32) {
+        if let Some(ref mut rasterizer) = self.output.rasterizer {
+            rasterizer.clear_texture_region(id, min_x, min_y, max_x, max_y);
+        }
+
+        let texture = match self.output.textures.get_mut(id) {
+            None => return,
+            Some(texture) => texture,
+        };
+
+        let (width, height) = texture.image.dimensions();
+        let max_x = (max_x.max(0) as u32).min(width);
+        let max_y = (max_y.max(0) as u32).min(height);
+        for y in (min_y.max(0) as u32)..max_y {
+            for x in (min_x.max(0) as u32)..max_x {
+                texture.image.put_pixel(x, y, Rgba([0, 0, 0, 0]));
+            }
+        }
+    }
+
+    fn has_texture(&self, id: &str) -> bool {
+        self.output.textures.contains_key(id)
+    }
+
+    fn set_scissor(&mut self, pos: Point, size: Size) {
+        if let Some(ref mut rasterizer) = self.output.rasterizer {
+            rasterizer.set_scissor(pos, size);
+        }
+        self.scissor = Some((pos, size));
+    }
+
+    fn clear_scissor(&mut self) {
+        if let Some(ref mut rasterizer) = self.output.rasterizer {
+            rasterizer.clear_scissor();
+        }
+        self.scissor = None;
+    }
+}
+
+/// An IO adapter that does not create a window or require a graphics
+/// context.  Rendering is recorded in memory as `DrawList`s, and input is
+/// taken from a scripted queue of `InputAction`s, one set per frame.
+pub struct HeadlessDisplay {
+    script: VecDeque<Vec<InputAction>>,
+    exit_on_script_end: bool,
+    output: Rc<RefCell<HeadlessOutput>>,
+}
+
+impl HeadlessDisplay {
+    /// Creates a headless display with no scripted input.  The display
+    /// will never request an exit on its own.
+    pub fn new() -> HeadlessDisplay {
+        debug!("Initialize Headless Display adapter.");
+        HeadlessDisplay {
+            script: VecDeque::new(),
+            exit_on_script_end: false,
+            output: Rc::new(RefCell::new(HeadlessOutput::default())),
+        }
+    }
+
+    /// Creates a headless display that will process the specified input
+    /// script, with each entry being the set of actions handled in one frame.
+    /// Once the script has been consumed, the display requests an exit from
+    /// the main loop.
+    pub fn with_script(script: Vec<Vec<InputAction>>) -> HeadlessDisplay {
+        let mut display = HeadlessDisplay::new();
+        display.script = script.into();
+        display.exit_on_script_end = true;
+        display
+    }
+
+    /// Adds a frame with the specified actions to the end of the input script
+    pub fn push_frame(&mut self, actions: Vec<InputAction>) {
+        self.script.push_back(actions);
+    }
+
+    /// Adds the specified number of frames with no input to the end of the
+    /// input script
+    pub fn push_idle_frames(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.script.push_back(Vec::new());
+        }
+    }
+
+    /// Causes each rendered frame to also be composited into an image of the
+    /// specified size by a `SoftwareRenderer`.  The most recent frame is then
+    /// available from the `rasterizer` of the `HeadlessOutput`
+    pub fn enable_rasterizer(&mut self, width: u32, height: u32) {
+        self.output.borrow_mut().rasterizer = Some(SoftwareRenderer::new(width, height));
+    }
+
+    /// Returns a shared handle to the recorded output of this display
+    pub fn output(&self) -> Rc<RefCell<HeadlessOutput>> {
+        Rc::clone(&self.output)
+    }
+}
+
+impl Default for HeadlessDisplay {
+    fn default() -> Self {
+        HeadlessDisplay::new()
+    }
+}
+
+impl IO for HeadlessDisplay {
+    fn process_input(&mut self, root: Rc<RefCell<Widget>>) {
+        let actions = match self.script.pop_front() {
+            None => return,
+            Some(actions) => actions,
+        };
+
+        for action in actions {
+            InputAction::handle_action(action, &root);
+        }
+    }
+
+    fn render_output(&mut self, root: Ref<Widget>, millis: u32) {
+        let mut output = self.output.borrow_mut();
+        output.draws.clear();
+        output.frames += 1;
+        if let Some(ref mut rasterizer) = output.rasterizer {
+            rasterizer.clear();
+        }
+
+        let (width, height) = Config::display_resolution();
+        let pixel_size = Point::new(width as i32, height as i32);
+
+        let mut renderer = HeadlessRenderer::new(&mut output);
+        root.draw(&mut renderer, pixel_size, millis);
+
+        Cursor::draw(&mut renderer, millis);
+
+        // thumbnails can only be captured when rasterizing
+        match output.rasterizer {
+            Some(ref rasterizer) => Thumbnail::capture(rasterizer.frame()),
+            None => Thumbnail::cancel_requests(),
+        }
+    }
+
+    fn get_display_configurations(&self) -> Vec<DisplayConfiguration> {
+        vec![DisplayConfiguration {
+            name: "Headless".to_string(),
+            index: 0,
+            resolutions: vec![Config::display_resolution()],
+        }]
+    }
+
+    fn is_exit(&self) -> bool {
+        self.exit_on_script_end && self.script.is_empty()
+    }
+}
@@ -18,7 +18,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::io::event::{ClickKind, Kind};
-use crate::io::{keyboard_event::Key, Event};
+use crate::io::{keyboard_event::Key, Event, InputRecorder};
 use crate::ui::{Cursor, Widget};
 
 #[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialOrd, PartialEq)]
@@ -30,6 +30,7 @@ pub enum InputAction {
     ToggleMap,
     ToggleJournal,
     ToggleFormation,
+    ToggleCombatLog,
     ShowMenu,
     EndTurn,
     ScrollUp,
@@ -40,6 +41,8 @@ pub enum InputAction {
     ZoomOut,
     QuickSave,
     SelectAll,
+    ReloadScripts,
+    ReloadResources,
     Exit,
     MouseMove(f32, f32),
     MouseDown(ClickKind),
@@ -51,6 +54,8 @@ pub enum InputAction {
 
 impl InputAction {
     pub fn handle_action(action: InputAction, root: &Rc<RefCell<Widget>>) {
+        InputRecorder::record_action(action);
+
         // don't spam tons of mouse move actions in the event logs
         match action {
             MouseMove(_, _) => (),
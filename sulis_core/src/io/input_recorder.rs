@@ -0,0 +1,159 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::io::InputAction;
+use crate::resource::write_json_to_file;
+use crate::ui::Widget;
+use crate::util::invalid_data_error;
+
+thread_local! {
+    static RECORDER: RefCell<InputRecorder> = RefCell::new(InputRecorder::default());
+}
+
+/// The input handled during a single frame of the main loop, along with the
+/// number of milliseconds that frame's update was run with
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RecordedFrame {
+    pub millis: u32,
+    pub actions: Vec<InputAction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct InputRecording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    pub fn read(path: &Path) -> Result<InputRecording, Error> {
+        let data = fs::read_to_string(path)?;
+
+        match serde_json::from_str(&data) {
+            Ok(recording) => Ok(recording),
+            Err(e) => invalid_data_error(&format!("{}", e)),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        write_json_to_file(path, self)
+    }
+}
+
+/// Records every `InputAction` handled by the main loop, along with the per
+/// frame elapsed millis, and plays back previously made recordings.  Used
+/// together with a save file, this allows exactly reproducing a play session.
+#[derive(Default)]
+pub struct InputRecorder {
+    recording: Option<(PathBuf, InputRecording)>,
+    current_frame: Vec<InputAction>,
+    replay: VecDeque<RecordedFrame>,
+}
+
+impl InputRecorder {
+    /// Begins recording all input.  The recording is written to the
+    /// specified path when `stop_recording` is called.
+    pub fn start_recording(path: PathBuf) {
+        info!("Recording input to {:?}", path);
+        RECORDER.with(|r| {
+            let mut recorder = r.borrow_mut();
+            recorder.recording = Some((path, InputRecording::default()));
+            recorder.current_frame.clear();
+        });
+    }
+
+    /// Stops any current recording, writing it out to its file
+    pub fn stop_recording() -> Result<(), Error> {
+        let (path, recording) = match RECORDER.with(|r| r.borrow_mut().recording.take()) {
+            None => return Ok(()),
+            Some(recording) => recording,
+        };
+
+        info!(
+            "Writing {} frames of recorded input to {:?}",
+            recording.frames.len(),
+            path
+        );
+        recording.write(&path)
+    }
+
+    pub fn is_recording() -> bool {
+        RECORDER.with(|r| r.borrow().recording.is_some())
+    }
+
+    /// Queues up the specified recording to be played back, starting with
+    /// the next frame of the main loop.  While a replay is in progress, input
+    /// from the IO is ignored.
+    pub fn start_replay(recording: InputRecording) {
+        info!("Starting replay of {} frames", recording.frames.len());
+        RECORDER.with(|r| r.borrow_mut().replay = recording.frames.into());
+    }
+
+    pub fn is_replaying() -> bool {
+        RECORDER.with(|r| !r.borrow().replay.is_empty())
+    }
+
+    pub fn cancel_replay() {
+        RECORDER.with(|r| r.borrow_mut().replay.clear());
+    }
+
+    /// Records the specified action as part of the current frame, if
+    /// a recording is active
+    pub fn record_action(action: InputAction) {
+        RECORDER.with(|r| {
+            let mut recorder = r.borrow_mut();
+            if recorder.recording.is_some() {
+                recorder.current_frame.push(action);
+            }
+        });
+    }
+
+    /// Completes the current frame, which was updated with the specified
+    /// elapsed millis
+    pub fn end_frame(millis: u32) {
+        RECORDER.with(|r| {
+            let mut recorder = r.borrow_mut();
+            let actions = recorder.current_frame.drain(..).collect();
+            if let Some((_, ref mut recording)) = recorder.recording {
+                recording.frames.push(RecordedFrame { millis, actions });
+            }
+        });
+    }
+
+    /// If a replay is in progress, handles all the actions for the next
+    /// frame of it and returns the elapsed millis for that frame.  Otherwise,
+    /// returns `None`
+    pub fn replay_frame(root: &Rc<RefCell<Widget>>) -> Option<u32> {
+        let frame = RECORDER.with(|r| r.borrow_mut().replay.pop_front())?;
+
+        for action in frame.actions {
+            InputAction::handle_action(action, root);
+        }
+
+        if !InputRecorder::is_replaying() {
+            info!("Replay complete");
+        }
+
+        Some(frame.millis)
+    }
+}
@@ -0,0 +1,365 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::io::*;
+use crate::resource::ResourceSet;
+use crate::ui::Widget;
+use crate::util::Point;
+
+type Image = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+/// A CPU renderer that composites `DrawList`s into an in memory image,
+/// closely following what the glium shaders do.  Textures are always
+/// sampled with nearest filtering, and no sRGB conversion is performed, so
+/// the output is suitable for comparison against other software rendered
+/// images, but will not exactly match what is shown on screen.
+pub struct SoftwareRenderer {
+    frame: Image,
+    textures: HashMap<String, Image>,
+    scissor: Option<(Point, Size)>,
+    ui_size: (f32, f32),
+}
+
+impl SoftwareRenderer {
+    /// Creates a new renderer with a frame of the specified size in pixels.
+    /// The UI coordinate space is mapped onto the full frame.
+    pub fn new(width: u32, height: u32) -> SoftwareRenderer {
+        let (ui_x, ui_y) = Config::ui_size();
+        SoftwareRenderer {
+            frame: ImageBuffer::from_pixel(width, height, Rgba([0, 0, 0, 255])),
+            textures: HashMap::new(),
+            scissor: None,
+            ui_size: (ui_x as f32, ui_y as f32),
+        }
+    }
+
+    /// Clears the frame to opaque black, in preparation for drawing a new frame.
+    /// Registered textures are kept.
+    pub fn clear(&mut self) {
+        for pixel in self.frame.pixels_mut() {
+            *pixel = Rgba([0, 0, 0, 255]);
+        }
+        self.scissor = None;
+    }
+
+    /// Clears the frame and draws the specified widget tree into it
+    pub fn render(&mut self, root: &Widget, millis: u32) {
+        self.clear();
+        let (width, height) = self.frame.dimensions();
+        root.draw(self, Point::new(width as i32, height as i32), millis);
+    }
+
+    pub fn frame(&self) -> &ImageBuffer<Rgba<u8>, Vec<u8>> {
+        &self.frame
+    }
+
+    pub fn texture(&self, id: &str) -> Option<&ImageBuffer<Rgba<u8>, Vec<u8>>> {
+        self.textures.get(id)
+    }
+
+    /// Writes the current frame out to the specified file.  The format
+    /// is determined from the file extension, normally `png`
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        self.frame
+            .save(path)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}", e)))
+    }
+
+    fn create_texture_if_missing(&mut self, texture_id: &str, kind: DrawListKind) {
+        if self.textures.contains_key(texture_id) {
+            return;
+        }
+
+        let image = match kind {
+            DrawListKind::Sprite => ResourceSet::spritesheet(texture_id).map(|s| s.image.clone()),
+            DrawListKind::Font => ResourceSet::font(texture_id).map(|f| f.image.clone()),
+        };
+
+        match image {
+            None => warn!("Unable to find image for texture '{}'", texture_id),
+            Some(image) => {
+                self.textures.insert(texture_id.to_string(), image);
+            }
+        }
+    }
+}
+
+impl GraphicsRenderer for SoftwareRenderer {
+    fn draw(&mut self, draw_list: DrawList) {
+        if draw_list.texture.is_empty() {
+            return;
+        }
+        self.create_texture_if_missing(&draw_list.texture, draw_list.kind);
+
+        let source = match self.textures.get(&draw_list.texture) {
+            None => return,
+            Some(source) => source,
+        };
+
+        let clip = scissor_rect(self.scissor, self.ui_size, self.frame.dimensions());
+        rasterize(&mut self.frame, source, &draw_list, self.ui_size, clip);
+    }
+
+    fn draw_to_texture(&mut self, texture_id: &str, draw_list: DrawList) {
+        self.create_texture_if_missing(&draw_list.texture, draw_list.kind);
+
+        // the source and target may be the same texture, so sample from a copy
+        let source = match self.textures.get(&draw_list.texture) {
+            None => return,
+            Some(source) => source.clone(),
+        };
+
+        let ui_size = self.ui_size;
+        let target = match self.textures.get_mut(texture_id) {
+            None => {
+                warn!("Attempted to draw to invalid texture '{}'", texture_id);
+                return;
+            }
+            Some(target) => target,
+        };
+
+        let (width, height) = target.dimensions();
+        rasterize(target, &source, &draw_list, ui_size, (0, 0, width, height));
+    }
+
+    fn register_texture(
+        &mut self,
+        id: &str,
+        image: ImageBuffer<Rgba<u8>, Vec<u8>>,
+        _min_filter: TextureMinFilter,
+        _mag_filter: TextureMagFilter,
+    ) {
+        self.textures.insert(id.to_string(), image);
+    }
+
+    fn clear_texture(&mut self, id: &str) {
+        if let Some(texture) = self.textures.get_mut(id) {
+            for pixel in texture.pixels_mut() {
+                *pixel = Rgba([0, 0, 0, 0]);
+            }
+        }
+    }
+
+    fn clear_texture_region(&mut self, id: &str, min_x: i32, min_y: i32, max_x: i32, max_y: i32) {
+        let texture = match self.textures.get_mut(id) {
+            None => return,
+            Some(texture) => texture,
+        };
+
+        let (width, height) = texture.dimensions();
+        let max_x = (max_x.max(0) as u32).min(width);
+        let max_y = (max_y.max(0) as u32).min(height);
+        for y in (min_y.max(0) as u32)..max_y {
+            for x in (min_x.max(0) as u32)..max_x {
+                texture.put_pixel(x, y, Rgba([0, 0, 0, 0]));
+            }
+        }
+    }
+
+    fn has_texture(&self, id: &str) -> bool {
+        self.textures.contains_key(id)
+    }
+
+    fn set_scissor(&mut self, pos: Point, size: Size) {
+        self.scissor = Some((pos, size));
+    }
+
+    fn clear_scissor(&mut self) {
+        self.scissor = None;
+    }
+}
+
+/// Converts the scissor, in UI coordinates, to a (min_x, min_y, max_x, max_y)
+/// pixel rect in the target
+fn scissor_rect(
+    scissor: Option<(Point, Size)>,
+    ui_size: (f32, f32),
+    dims: (u32, u32),
+) -> (u32, u32, u32, u32) {
+    let (pos, size) = match scissor {
+        None => return (0, 0, dims.0, dims.1),
+        Some(scissor) => scissor,
+    };
+
+    let scale_x = dims.0 as f32 / ui_size.0;
+    let scale_y = dims.1 as f32 / ui_size.1;
+    let clamp_x = |x: i32| ((x as f32 * scale_x).max(0.0) as u32).min(dims.0);
+    let clamp_y = |y: i32| ((y as f32 * scale_y).max(0.0) as u32).min(dims.1);
+
+    (
+        clamp_x(pos.x),
+        clamp_y(pos.y),
+        clamp_x(pos.x + size.width),
+        clamp_y(pos.y + size.height),
+    )
+}
+
+/// Draws the triangles in the draw list onto the target, with the same
+/// transforms as the glium vertex shader.  UI coordinates are mapped onto the
+/// full target, with the draw list scale anchored at the top left corner
+fn rasterize(
+    target: &mut Image,
+    source: &Image,
+    draw_list: &DrawList,
+    ui_size: (f32, f32),
+    clip: (u32, u32, u32, u32),
+) {
+    let (width, height) = target.dimensions();
+    let (w, h) = (width as f32, height as f32);
+    let scale = draw_list.scale;
+
+    // transform a vertex position to target pixel coordinates, with y down
+    let transform = |pos: [f32; 2]| -> (f32, f32) {
+        let ndc_x = scale[0] * (2.0 * pos[0] / ui_size.0 - 1.0) + scale[0] - 1.0;
+        let ndc_y = scale[1] * (2.0 * pos[1] / ui_size.1 - 1.0) + 1.0 - scale[1];
+        ((ndc_x + 1.0) / 2.0 * w, (1.0 - ndc_y) / 2.0 * h)
+    };
+
+    for tri in draw_list.quads.chunks(3) {
+        if tri.len() < 3 {
+            break;
+        }
+
+        let p: Vec<(f32, f32)> = tri.iter().map(|v| transform(v.position)).collect();
+        let area = edge(p[0], p[1], p[2]);
+        if area.abs() < f32::EPSILON {
+            continue;
+        }
+
+        let min_x = p.iter().map(|p| p.0).fold(w, f32::min).floor().max(clip.0 as f32) as u32;
+        let max_x = p.iter().map(|p| p.0).fold(0.0, f32::max).ceil().min(clip.2 as f32) as u32;
+        let min_y = p.iter().map(|p| p.1).fold(h, f32::min).floor().max(clip.1 as f32) as u32;
+        let max_y = p.iter().map(|p| p.1).fold(0.0, f32::max).ceil().min(clip.3 as f32) as u32;
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let center = (x as f32 + 0.5, y as f32 + 0.5);
+                let w0 = edge(p[1], p[2], center) / area;
+                let w1 = edge(p[2], p[0], center) / area;
+                let w2 = edge(p[0], p[1], center) / area;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                let u = w0 * tri[0].tex_coords[0] + w1 * tri[1].tex_coords[0]
+                    + w2 * tri[2].tex_coords[0];
+                let v = w0 * tri[0].tex_coords[1] + w1 * tri[1].tex_coords[1]
+                    + w2 * tri[2].tex_coords[1];
+
+                let color = shade(sample(source, u, v), draw_list);
+                let dest = target.get_pixel_mut(x, y);
+                *dest = blend(color, *dest);
+            }
+        }
+    }
+}
+
+fn edge(a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Nearest neighbor sampling.  Texture coordinates have v = 0 at the bottom
+/// of the image, as in OpenGL
+fn sample(image: &Image, u: f32, v: f32) -> [f32; 4] {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return [0.0; 4];
+    }
+
+    let x = ((u * width as f32) as i64).clamp(0, width as i64 - 1) as u32;
+    let y = (((1.0 - v) * height as f32) as i64).clamp(0, height as i64 - 1) as u32;
+    let p = image.get_pixel(x, y);
+    [
+        p[0] as f32 / 255.0,
+        p[1] as f32 / 255.0,
+        p[2] as f32 / 255.0,
+        p[3] as f32 / 255.0,
+    ]
+}
+
+/// Applies the color filter, secondary color, and hue swap in the same way
+/// as the glium fragment shaders
+fn shade(tex: [f32; 4], draw_list: &DrawList) -> [f32; 4] {
+    let filter = draw_list.color_filter;
+
+    if draw_list.color_swap_enabled {
+        let (hue, sat, val) = rgb_to_hsv(tex[0], tex[1], tex[2]);
+        if hue < 0.9 && hue > 0.8 {
+            let (r, g, b) = hsv_to_rgb(draw_list.swap_hue, sat, val);
+            return [r, g, b, tex[3]];
+        }
+
+        return [
+            filter[0] * tex[0],
+            filter[1] * tex[1],
+            filter[2] * tex[2],
+            filter[3] * tex[3],
+        ];
+    }
+
+    let sec = draw_list.color_sec;
+    [
+        filter[0] * tex[0] + sec[0],
+        filter[1] * tex[1] + sec[1],
+        filter[2] * tex[2] + sec[2],
+        filter[3] * tex[3] + sec[3],
+    ]
+}
+
+/// Standard alpha blending of the source color over the destination pixel
+fn blend(src: [f32; 4], dest: Rgba<u8>) -> Rgba<u8> {
+    let alpha = src[3].clamp(0.0, 1.0);
+    let mut out = [0u8; 4];
+    for i in 0..4 {
+        let dest = dest[i] as f32 / 255.0;
+        let val = src[i].clamp(0.0, 1.0) * alpha + dest * (1.0 - alpha);
+        out[i] = (val * 255.0).round() as u8;
+    }
+    Rgba(out)
+}
+
+fn rgb_to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta <= 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0) / 6.0
+    } else if max == g {
+        ((b - r) / delta + 2.0) / 6.0
+    } else {
+        ((r - g) / delta + 4.0) / 6.0
+    };
+
+    let sat = if max <= 0.0 { 0.0 } else { delta / max };
+    (hue, sat, max)
+}
+
+fn hsv_to_rgb(hue: f32, sat: f32, val: f32) -> (f32, f32, f32) {
+    let channel = |offset: f32| {
+        let k = (offset + hue * 6.0).rem_euclid(6.0);
+        val - val * sat * k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+
+    (channel(5.0), channel(3.0), channel(1.0))
+}
@@ -0,0 +1,106 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Reduced size screenshots of the display, such as the thumbnails written
+//! alongside save files.  A thumbnail is requested, and then captured by the
+//! IO adapter once it has finished rendering the next frame.
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::extern_image::{imageops, FilterType, GenericImageView, ImageBuffer, Rgba};
+use crate::util::{Point, Size};
+
+/// The width of thumbnails in pixels.  The height is determined by the
+/// aspect ratio of the captured region.
+pub const THUMBNAIL_WIDTH: u32 = 192;
+
+thread_local! {
+    static REGION: RefCell<Option<(Point, Size)>> = RefCell::new(None);
+    static REQUESTS: RefCell<Vec<PathBuf>> = RefCell::new(Vec::new());
+}
+
+pub struct Thumbnail;
+
+impl Thumbnail {
+    /// Sets the region of the display, in UI coordinates, that thumbnails
+    /// are captured from.  If `None`, the whole display is captured.
+    pub fn set_region(region: Option<(Point, Size)>) {
+        REGION.with(|r| *r.borrow_mut() = region);
+    }
+
+    /// Requests a thumbnail of the next rendered frame, to be written as a
+    /// PNG image to the specified path
+    pub fn request(path: PathBuf) {
+        REQUESTS.with(|r| r.borrow_mut().push(path));
+    }
+
+    pub(crate) fn is_requested() -> bool {
+        REQUESTS.with(|r| !r.borrow().is_empty())
+    }
+
+    /// Discards all requests, for adapters that are unable to capture
+    pub(crate) fn cancel_requests() {
+        REQUESTS.with(|r| r.borrow_mut().clear());
+    }
+
+    /// Creates and writes out all requested thumbnails from the specified
+    /// frame, which must contain the entire display with its origin at the
+    /// top left
+    pub(crate) fn capture(frame: &ImageBuffer<Rgba<u8>, Vec<u8>>) {
+        let paths: Vec<PathBuf> = REQUESTS.with(|r| r.borrow_mut().drain(..).collect());
+        if paths.is_empty() {
+            return;
+        }
+
+        let thumbnail = Thumbnail::create(frame);
+        for path in paths {
+            match thumbnail.save(&path) {
+                Ok(()) => debug!("Wrote thumbnail to {:?}", path),
+                Err(e) => {
+                    warn!("Unable to write thumbnail to {:?}", path);
+                    warn!("{}", e);
+                }
+            }
+        }
+    }
+
+    fn create(frame: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        let (width, height) = frame.dimensions();
+        let (ui_width, ui_height) = Config::ui_size();
+        let scale_x = width as f32 / ui_width as f32;
+        let scale_y = height as f32 / ui_height as f32;
+
+        let region = REGION.with(|r| *r.borrow());
+        let (x, y, w, h) = match region {
+            None => (0, 0, width, height),
+            Some((pos, size)) => {
+                let x = ((pos.x as f32 * scale_x) as u32).min(width - 1);
+                let y = ((pos.y as f32 * scale_y) as u32).min(height - 1);
+                let w = ((size.width as f32 * scale_x) as u32).max(1).min(width - x);
+                let h = ((size.height as f32 * scale_y) as u32)
+                    .max(1)
+                    .min(height - y);
+                (x, y, w, h)
+            }
+        };
+
+        let thumb_height = ((THUMBNAIL_WIDTH * h) / w).max(1);
+        let region = frame.view(x, y, w, h);
+        imageops::resize(&region, THUMBNAIL_WIDTH, thumb_height, FilterType::Triangle)
+    }
+}
@@ -169,7 +169,7 @@ impl AnimationState {
     ///
     /// # Examples
     /// ```
-    /// use ui::AnimationState;
+    /// use sulis_core::ui::AnimationState;
     /// let state = AnimationState::parse("hover + active");
     /// let state2 = AnimationState::parse("pressed");
     /// ```
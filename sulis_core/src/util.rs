@@ -37,10 +37,11 @@ use backtrace::Backtrace;
 use flexi_logger::{opt_format, Duplicate, Logger};
 use rand::{self, distributions::uniform::SampleUniform, seq::SliceRandom, Rng};
 use rand_pcg::Pcg64Mcg;
+use serde::{Deserialize, Deserializer, Serializer};
 use serde_yaml;
 
 use crate::config::{self, Config};
-use crate::io::{MainLoopUpdater, IO};
+use crate::io::{InputRecorder, MainLoopUpdater, IO};
 use crate::resource::write_to_file;
 use crate::ui::Widget;
 
@@ -62,11 +63,63 @@ pub fn approx_eq(a: f32, b: f32) -> bool {
     i32::abs(a_int - b_int) <= MAX_ULPS
 }
 
+/// A random generator that can be seeded and saved, so that the values it
+/// produces are reproducible.  The `u128` seed and generator state are
+/// serialized as strings, as `serde_json::Value` can't hold integers that
+/// large.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct ReproducibleRandom {
+    #[serde(serialize_with = "ser_u128", deserialize_with = "de_u128")]
     seed: u128,
+
+    #[serde(serialize_with = "ser_pcg", deserialize_with = "de_pcg")]
     gen: Pcg64Mcg,
 }
 
+/// The internal state of a `Pcg64Mcg`, which is only exposed through serde
+#[derive(Deserialize)]
+struct PcgState {
+    state: u128,
+}
+
+fn ser_u128<S>(input: &u128, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&input.to_string())
+}
+
+fn de_u128<'de, D>(deserializer: D) -> Result<u128, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(|err| Error::custom(format!("{}", err)))
+}
+
+fn ser_pcg<S>(input: &Pcg64Mcg, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    use serde::ser::Error;
+    let state: PcgState = serde_json::to_string(input)
+        .and_then(|json| serde_json::from_str(&json))
+        .map_err(|err| Error::custom(err.to_string()))?;
+    ser_u128(&state.state, serializer)
+}
+
+fn de_pcg<'de, D>(deserializer: D) -> Result<Pcg64Mcg, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // the state is always odd, so creating the generator from it leaves it
+    // unchanged
+    let state = de_u128(deserializer)?;
+    Ok(Pcg64Mcg::new(state))
+}
+
 impl ReproducibleRandom {
     pub fn new(seed: Option<u128>) -> ReproducibleRandom {
         // TODO only seed with u64 for now because serde_yaml doesn't serialize u128 correctly
@@ -386,7 +439,17 @@ pub fn main_loop(
         last_start_time = time::Instant::now();
         let total_elapsed = get_elapsed_millis(main_loop_start_time.elapsed());
 
-        io.process_input(Rc::clone(&root));
+        // when replaying recorded input, the recorded frame time is used
+        // in place of the actual frame time
+        let last_elapsed = match InputRecorder::replay_frame(&root) {
+            Some(millis) => millis,
+            None => {
+                io.process_input(Rc::clone(&root));
+                last_elapsed
+            }
+        };
+        InputRecorder::end_frame(last_elapsed);
+
         updater.update(&root, last_elapsed);
 
         if let Err(e) = Widget::update(&root, last_elapsed) {
@@ -396,7 +459,7 @@ pub fn main_loop(
 
         io.render_output(root.borrow(), total_elapsed);
 
-        if updater.is_exit() {
+        if updater.is_exit() || io.is_exit() {
             trace!("Exiting main loop.");
             break;
         }
@@ -111,6 +111,7 @@ impl ActorCreatorWindow {
             reward: None,
             abilities: Vec::new(),
             ai: None,
+            schedule: None,
         };
 
         match write_to_file(&filename, &actor) {
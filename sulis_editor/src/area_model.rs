@@ -26,13 +26,13 @@ use sulis_core::ui::{animation_state, LineRenderer};
 use sulis_core::util::{Point, Size};
 use sulis_module::area::*;
 use sulis_module::generator::{is_removal, TilesModel};
-use sulis_module::{Actor, Encounter, Module, Prop};
+use sulis_module::{Actor, Encounter, Module, Prop, Schedule};
 
 pub struct AreaModel {
     pub config: EditorConfig,
 
     tiles: TilesModel,
-    actors: Vec<(Point, Rc<Actor>, Option<String>)>,
+    actors: Vec<(Point, Rc<Actor>, Option<String>, Option<Schedule>)>,
     props: Vec<PropData>,
     encounters: Vec<EncounterData>,
     transitions: Vec<Transition>,
@@ -162,11 +162,11 @@ impl AreaModel {
             return;
         }
 
-        self.actors.push((Point::new(x, y), actor, None));
+        self.actors.push((Point::new(x, y), actor, None, None));
     }
 
     pub fn remove_actors_within(&mut self, x: i32, y: i32, width: i32, height: i32) {
-        self.actors.retain(|&(pos, ref actor, _)| {
+        self.actors.retain(|&(pos, ref actor, ..)| {
             !is_removal(
                 pos,
                 actor.race.size.width,
@@ -187,7 +187,7 @@ impl AreaModel {
         height: i32,
     ) -> Vec<(Point, Rc<Actor>)> {
         let mut actors = Vec::new();
-        for &(pos, ref actor, _) in self.actors.iter() {
+        for &(pos, ref actor, ..) in self.actors.iter() {
             if !is_removal(
                 pos,
                 actor.race.size.width,
@@ -393,7 +393,7 @@ impl AreaModel {
             renderer.draw(draw_list);
         }
 
-        for &(pos, ref actor, _) in self.actors.iter() {
+        for &(pos, ref actor, ..) in self.actors.iter() {
             let w = actor.race.size.width as f32 / 2.0;
             let h = actor.race.size.height as f32 / 2.0;
             actor.draw(
@@ -581,8 +581,12 @@ impl AreaModel {
                 Some(actor) => actor,
             };
 
-            self.actors
-                .push((actor_data.location, actor, actor_data.unique_id));
+            self.actors.push((
+                actor_data.location,
+                actor,
+                actor_data.unique_id,
+                actor_data.schedule,
+            ));
         }
 
         trace!("Loading area encounters.");
@@ -711,11 +715,12 @@ impl AreaModel {
 
         trace!("Saving actors.");
         let mut actors: Vec<ActorData> = Vec::new();
-        for &(pos, ref actor, ref unique_id) in self.actors.iter() {
+        for &(pos, ref actor, ref unique_id, ref schedule) in self.actors.iter() {
             actors.push(ActorData {
                 id: actor.id.to_string(),
                 unique_id: unique_id.clone(),
                 location: pos,
+                schedule: schedule.clone(),
             });
         }
 
@@ -28,7 +28,7 @@ use sulis_core::util::unable_to_create_error;
 
 use crate::{
     AITemplate, Ability, Class, Conversation, ImageLayer, ImageLayerSet, InventoryBuilder,
-    LootList, Module, Race,
+    LootList, Module, Race, Schedule,
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -146,6 +146,7 @@ pub struct Actor {
     pub abilities: Vec<OwnedAbility>,
 
     pub ai: Option<Rc<AITemplate>>,
+    pub schedule: Option<Rc<Schedule>>,
 }
 
 impl PartialEq for Actor {
@@ -226,6 +227,7 @@ impl Actor {
             reward: other.reward.clone(),
             abilities,
             ai: other.ai.clone(),
+            schedule: other.schedule.clone(),
         }
     }
 
@@ -365,9 +367,56 @@ impl Actor {
             hair_color: builder.hair_color,
             abilities,
             ai,
+            schedule: builder.schedule.map(Rc::new),
         })
     }
 
+    /// Creates a builder that will re-create this actor, including all of its
+    /// levels and abilities
+    pub fn to_builder(&self) -> ActorBuilder {
+        let mut levels = HashMap::new();
+        for (ref class, level) in self.levels.iter() {
+            levels.insert(class.id.to_string(), *level);
+        }
+
+        let reward = self.reward.as_ref().map(|reward| RewardBuilder {
+            xp: reward.xp,
+            loot: reward.loot.as_ref().map(|l| l.id.to_string()),
+            loot_chance: Some(reward.loot_chance),
+        });
+
+        let mut abilities: Vec<String> = Vec::new();
+        for owned_ability in self.abilities.iter() {
+            for _ in 0..(owned_ability.level + 1) {
+                abilities.push(owned_ability.ability.id.to_string());
+            }
+        }
+
+        let ai = self.ai.as_ref().map(|ai| ai.id.to_string());
+
+        ActorBuilder {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            race: self.race.id.to_string(),
+            sex: Some(self.sex),
+            portrait: self.portrait.as_ref().map(|p| p.id().to_string()),
+            attributes: self.attributes,
+            conversation: self.conversation.as_ref().map(|c| c.id.to_string()),
+            faction: Some(self.faction()),
+            images: self.builder_images.clone(),
+            hue: self.hue,
+            hair_color: self.hair_color,
+            skin_color: self.skin_color,
+            inventory: self.inventory.clone(),
+            levels,
+            xp: Some(self.xp),
+            reward,
+            abilities,
+            ai,
+            schedule: self.schedule.as_ref().map(|s| (**s).clone()),
+        }
+    }
+
     pub fn faction(&self) -> Faction {
         self.faction
     }
@@ -472,4 +521,7 @@ pub struct ActorBuilder {
     pub reward: Option<RewardBuilder>,
     pub abilities: Vec<String>,
     pub ai: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
 }
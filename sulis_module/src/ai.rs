@@ -32,4 +32,232 @@ pub struct AITemplate {
     pub id: String,
     pub script: String,
     pub hooks: HashMap<FuncKind, String>,
+
+    /// The role of actors with this template in their encounter group.  If
+    /// not specified, actors with ranged attacks are `Ranged` and all
+    /// others are `Tank`
+    #[serde(default)]
+    pub role: Option<AIRole>,
+
+    /// If present, the native utility AI chooses this actor's actions
+    /// instead of the `ai_action` function of the `script`
+    #[serde(default)]
+    pub utility: Option<UtilityAI>,
+}
+
+/// The part an actor plays in its encounter group's tactics
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub enum AIRole {
+    /// Holds the line, protecting other group members from melee attackers
+    Tank,
+
+    /// Attacks from range, keeping out of reach of hostiles
+    Ranged,
+
+    /// Heals and buffs the group, keeping out of reach of hostiles
+    Healer,
+}
+
+/// A behavior for party members controlled by the AI, rather than manually
+/// by the player.  Each preset uses the AI template set for it in the rules.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub enum PartyAIPreset {
+    Aggressive,
+    Defensive,
+    Support,
+    Passive,
+}
+
+impl PartyAIPreset {
+    pub fn from_name(val: &str) -> Option<PartyAIPreset> {
+        match val {
+            "Aggressive" => Some(PartyAIPreset::Aggressive),
+            "Defensive" => Some(PartyAIPreset::Defensive),
+            "Support" => Some(PartyAIPreset::Support),
+            "Passive" => Some(PartyAIPreset::Passive),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PartyAIPreset::Aggressive => "Aggressive",
+            PartyAIPreset::Defensive => "Defensive",
+            PartyAIPreset::Support => "Support",
+            PartyAIPreset::Passive => "Passive",
+        }
+    }
+
+    /// Returns the preset following the specified one when cycling through
+    /// them, where `None` is manual control
+    pub fn cycle(preset: Option<PartyAIPreset>) -> Option<PartyAIPreset> {
+        use PartyAIPreset::*;
+        match preset {
+            None => Some(Aggressive),
+            Some(Aggressive) => Some(Defensive),
+            Some(Defensive) => Some(Support),
+            Some(Support) => Some(Passive),
+            Some(Passive) => None,
+        }
+    }
+}
+
+/// Configuration of the native utility AI.  Each candidate action is scored
+/// as its base weight plus a set of weighted considerations, and the highest
+/// scoring action with a positive score is taken.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UtilityAI {
+    /// A function in the template `script`, called as
+    /// `func(parent, actions)` with the scored candidate actions, best
+    /// first, before the best one is taken.  It may return an AI state to
+    /// override the native choice, or nil to keep it.
+    #[serde(default)]
+    pub script_override: Option<String>,
+
+    #[serde(default)]
+    pub weights: UtilityWeights,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct UtilityWeights {
+    /// Base score of a weapon attack
+    pub attack: f32,
+
+    /// Base score of using an ability
+    pub ability: f32,
+
+    /// Added to abilities and items scaled by their AI priority, with the
+    /// lowest priority numbers gaining the most
+    pub priority: f32,
+
+    /// Base score of using an item
+    pub item: f32,
+
+    /// Base score of moving to a position flanking a target, for melee
+    /// attackers not yet flanking the target
+    pub move_to_flank: f32,
+
+    /// Base score of moving away from hostiles, multiplied by the fraction
+    /// of hit points lost and the number of hostiles threatening the actor
+    pub retreat: f32,
+
+    /// Subtracted per tile of distance to the target, as a fraction of the
+    /// actor's vision distance
+    pub distance: f32,
+
+    /// Multiplied by the fraction of hit points the target has lost
+    pub target_hp: f32,
+
+    /// Added for targets that threaten the actor in melee
+    pub threat: f32,
+
+    /// Added for attacks that will flank the target
+    pub flanking: f32,
+
+    /// Heal abilities and items are only used on targets below this
+    /// fraction of their maximum hit points
+    pub heal_threshold: f32,
+
+    /// Added for targets with an active effect with each tag
+    pub target_effect_tags: HashMap<String, f32>,
+
+    /// Added to the retreat score while the actor has an active effect
+    /// with each tag
+    pub retreat_effect_tags: HashMap<String, f32>,
+
+    /// Multiplied by the improvement in position score when moving to the
+    /// best nearby tactical position
+    pub reposition: f32,
+
+    /// Weights used when scoring tactical positions
+    pub position: PositionWeights,
+
+    /// Added for targets that are the shared focus of the actor's group
+    pub focus: f32,
+
+    /// Added for tanks attacking hostiles that threaten other members of
+    /// their group
+    pub protect: f32,
+
+    /// Added for healers using heal and buff abilities and items
+    pub support: f32,
+
+    /// Added to the retreat score while the actor's group is retreating
+    pub group_retreat: f32,
+}
+
+impl Default for UtilityWeights {
+    fn default() -> UtilityWeights {
+        let mut retreat_effect_tags = HashMap::new();
+        retreat_effect_tags.insert("fear".to_string(), 10.0);
+
+        UtilityWeights {
+            attack: 1.0,
+            ability: 1.0,
+            priority: 0.5,
+            item: 0.5,
+            move_to_flank: 1.0,
+            retreat: 0.0,
+            distance: 0.5,
+            target_hp: 0.5,
+            threat: 0.5,
+            flanking: 0.5,
+            heal_threshold: 0.5,
+            target_effect_tags: HashMap::new(),
+            retreat_effect_tags,
+            reposition: 0.5,
+            position: PositionWeights::default(),
+            focus: 1.0,
+            protect: 0.5,
+            support: 0.5,
+            group_retreat: 10.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct PositionWeights {
+    /// Added for positions where melee attacks against the target would
+    /// flank it
+    pub flanking: f32,
+
+    /// Subtracted for each hostile able to attack the position, with
+    /// ranged attackers counting half
+    pub threat: f32,
+
+    /// Subtracted for positions covered by a surface created by a hostile
+    pub surface: f32,
+
+    /// Added for ranged attackers with line of sight to a hostile in range
+    pub line_of_sight: f32,
+
+    /// Added for melee attackers in positions with few open adjacent
+    /// tiles, such as doorways and corridors
+    pub chokepoint: f32,
+
+    /// Subtracted per square moved to reach the position
+    pub movement: f32,
+
+    /// Subtracted for each nearby member of the same group, while any
+    /// hostile has an area of effect ability
+    pub spread: f32,
+}
+
+impl Default for PositionWeights {
+    fn default() -> PositionWeights {
+        PositionWeights {
+            flanking: 1.0,
+            threat: 0.5,
+            surface: 2.0,
+            line_of_sight: 1.0,
+            chokepoint: 0.25,
+            movement: 0.05,
+            spread: 0.5,
+        }
+    }
 }
@@ -42,7 +42,7 @@ use sulis_core::resource::{ResourceSet, Sprite};
 use sulis_core::util::{unable_to_create_error, Point, Size};
 
 use crate::generator::{EncounterParams, EncounterParamsBuilder, PropParams, PropParamsBuilder};
-use crate::{Encounter, ItemListEntrySaveState, Module, ObjectSize, OnTrigger, Prop};
+use crate::{Encounter, ItemListEntrySaveState, Module, ObjectSize, OnTrigger, Prop, Schedule};
 
 pub const MAX_AREA_SIZE: i32 = 128;
 
@@ -79,6 +79,10 @@ pub struct ActorData {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unique_id: Option<String>,
+
+    /// If specified, replaces the actor's own schedule for this instance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
 }
 
 #[derive(Clone)]
@@ -181,6 +185,24 @@ impl Area {
             transitions.push(transition);
         }
 
+        for actor_data in builder.actors.iter() {
+            let schedule = match actor_data.schedule {
+                None => continue,
+                Some(ref schedule) => schedule,
+            };
+
+            let waypoints = schedule.activities.iter().flat_map(|a| a.waypoints.iter());
+            for waypoint in waypoints {
+                let p = waypoint.location;
+                if !p.in_bounds(builder.width as i32, builder.height as i32) {
+                    warn!(
+                        "Schedule waypoint {:?} for actor '{}' falls outside area bounds",
+                        p, actor_data.id
+                    );
+                }
+            }
+        }
+
         let mut triggers: Vec<Trigger> = Vec::new();
         for tbuilder in &builder.triggers {
             triggers.push(Trigger {
@@ -18,7 +18,7 @@ use std::io::Error;
 use std::rc::Rc;
 
 use crate::{Actor, Module};
-use sulis_core::util::{gen_rand, unable_to_create_error};
+use sulis_core::util::{unable_to_create_error, ReproducibleRandom};
 
 struct Entry {
     actor: Rc<Actor>,
@@ -30,6 +30,7 @@ struct Entry {
 pub struct Encounter {
     pub id: String,
     pub auto_spawn: bool,
+    pub tactics: GroupTactics,
     min_gen_actors: u32,
     max_gen_actors: u32,
     entries: Vec<Entry>,
@@ -66,6 +67,7 @@ impl Encounter {
         Ok(Encounter {
             id: builder.id,
             auto_spawn: builder.auto_spawn,
+            tactics: builder.tactics,
             min_gen_actors: builder.min_gen_actors,
             max_gen_actors: builder.max_gen_actors,
             entries,
@@ -73,12 +75,12 @@ impl Encounter {
         })
     }
 
-    fn gen_actor(&self) -> Option<(Rc<Actor>, Option<String>)> {
+    fn gen_actor(&self, rand: &mut ReproducibleRandom) -> Option<(Rc<Actor>, Option<String>)> {
         if self.total_weight == 0 {
             return None;
         }
 
-        let roll = gen_rand(0, self.total_weight);
+        let roll = rand.gen(0, self.total_weight);
         let mut cur_weight = 0;
         for entry in self.entries.iter() {
             cur_weight += entry.weight;
@@ -90,12 +92,12 @@ impl Encounter {
         None
     }
 
-    pub fn gen_actors(&self) -> Vec<(Rc<Actor>, Option<String>)> {
+    pub fn gen_actors(&self, rand: &mut ReproducibleRandom) -> Vec<(Rc<Actor>, Option<String>)> {
         let mut actors = Vec::new();
 
-        let num = gen_rand(self.min_gen_actors, self.max_gen_actors + 1);
+        let num = rand.gen(self.min_gen_actors, self.max_gen_actors + 1);
         for _ in 0..num {
-            match self.gen_actor() {
+            match self.gen_actor(rand) {
                 None => {
                     warn!("Unable to generate actor for encounter '{}'", self.id);
                     continue;
@@ -124,6 +126,44 @@ pub struct EncounterBuilder {
     min_gen_actors: u32,
     max_gen_actors: u32,
     entries: Vec<EntryBuilder>,
+
+    #[serde(default)]
+    tactics: GroupTactics,
+}
+
+/// How the actors spawned by an encounter coordinate as a group
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GroupTactics {
+    /// Whether group members prefer to attack a single shared target
+    pub focus: bool,
+
+    /// Whether group members spread out while any hostile has an area of
+    /// effect ability
+    pub spread: bool,
+
+    /// The group retreats together once its strength, the fraction of its
+    /// total hit points remaining, drops below this.  Zero to never retreat
+    pub retreat_below: f32,
+
+    /// Reinforcements are called once the group's strength drops below this
+    pub reinforce_below: f32,
+
+    /// The number of times reinforcements may be called.  Each wave is
+    /// generated from the encounter in the same way as the initial actors
+    pub reinforcements: u32,
+}
+
+impl Default for GroupTactics {
+    fn default() -> GroupTactics {
+        GroupTactics {
+            focus: true,
+            spread: true,
+            retreat_below: 0.0,
+            reinforce_below: 0.5,
+            reinforcements: 0,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -32,7 +32,7 @@ pub use self::actor::Faction;
 pub use self::actor::Sex;
 
 pub mod ai;
-pub use self::ai::AITemplate;
+pub use self::ai::{AIRole, AITemplate, PartyAIPreset, PositionWeights, UtilityAI, UtilityWeights};
 
 pub mod area;
 pub use self::area::Area;
@@ -55,7 +55,7 @@ pub use self::on_trigger::MerchantData;
 pub use self::on_trigger::OnTrigger;
 
 pub mod encounter;
-pub use self::encounter::Encounter;
+pub use self::encounter::{Encounter, GroupTactics};
 
 pub mod campaign;
 pub use self::campaign::Campaign;
@@ -100,14 +100,21 @@ pub use self::quest::Quest;
 pub mod race;
 pub use self::race::Race;
 
+pub mod schedule;
+pub use self::schedule::Schedule;
+
 pub mod rules;
 pub use self::rules::bonus;
 pub use self::rules::{
-    AccuracyKind, Armor, ArmorKind, Attack, AttackBonuses, AttackKind, Attribute, AttributeList,
-    Bonus, BonusKind, BonusList, Damage, DamageKind, DamageList, HitFlags, HitKind, ItemKind,
-    QuickSlot, Resistance, Rules, Slot, StatList, Time, WeaponKind, WeaponStyle, ROUND_TIME_MILLIS,
+    AccuracyKind, Armor, ArmorKind, Attack, AttackBonuses, AttackKind, AttackRoll, Attribute,
+    AttributeList, Bonus, BonusKind, BonusList, Damage, DamageKind, DamageList, DamageRoll,
+    Difficulty, HitFlags, HitKind, ItemKind, QuickSlot, Resistance, Rules, Slot, StatList, Time,
+    WeaponKind, WeaponStyle, ROUND_TIME_MILLIS,
 };
 
+pub mod validator;
+pub use self::validator::ValidationError;
+
 use std::time;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -115,7 +122,7 @@ use std::ffi::OsStr;
 use std::fmt::{self, Display};
 use std::fs;
 use std::io::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use sulis_core::config::{self, Config};
@@ -147,6 +154,21 @@ thread_local! {
     static MODULE: RefCell<Module> = RefCell::new(Module::default());
 }
 
+/// The kinds of resources that may be reloaded with `Module::reload_resources`,
+/// in the order they are created.  Each kind may hold references to the kinds
+/// before it.
+const RELOAD_KINDS: [YamlResourceKind; 9] = [
+    YamlResourceKind::Ability,
+    YamlResourceKind::AbilityList,
+    YamlResourceKind::Class,
+    YamlResourceKind::Item,
+    YamlResourceKind::LootList,
+    YamlResourceKind::Prop,
+    YamlResourceKind::Conversation,
+    YamlResourceKind::Actor,
+    YamlResourceKind::Encounter,
+];
+
 pub struct Module {
     rules: Option<Rc<Rules>>,
     campaign: Option<Rc<Campaign>>,
@@ -178,6 +200,10 @@ pub struct Module {
     generators: HashMap<String, Rc<AreaGenerator>>,
 
     root_dir: Option<String>,
+    resource_dirs: Vec<String>,
+
+    // the YAML that each of the RELOAD_KINDS was created from
+    reload_yaml: HashMap<YamlResourceKind, HashMap<String, serde_yaml::Value>>,
     init: bool,
 }
 
@@ -271,6 +297,16 @@ impl Module {
         modules
     }
 
+    /// Returns the info of each mod that is currently loaded, in load order
+    pub fn active_mods() -> Vec<ModificationInfo> {
+        let dirs = MODULE.with(|m| m.borrow().resource_dirs.clone());
+        dirs.into_iter()
+            .map(PathBuf::from)
+            .filter(|dir| dir.join("mod.yml").is_file())
+            .filter_map(|dir| ModificationInfo::from_dir(dir).ok())
+            .collect()
+    }
+
     pub fn delete_character(id: &str) {
         // TODO don't assume ID = filename
         let mut path = config::USER_DIR.clone();
@@ -414,6 +450,12 @@ impl Module {
 
         let campaign_builder: CampaignBuilder = read_builder(campaign_yaml)?;
 
+        let mut reload_yaml = HashMap::new();
+        for kind in RELOAD_KINDS.iter() {
+            let resources = yaml.resources.get(kind).cloned().unwrap_or_default();
+            reload_yaml.insert(*kind, resources);
+        }
+
         let builder_set = ModuleBuilder::from_yaml(&mut yaml)?;
         let area_builders = MODULE.with(|module| {
             let mut module = module.borrow_mut();
@@ -447,6 +489,8 @@ impl Module {
             expand_include_directives(&mut module.scripts);
 
             module.root_dir = Some(dirs[1].to_string());
+            module.resource_dirs = dirs.clone();
+            module.reload_yaml = reload_yaml;
 
             for (id, adj) in builder_set.item_adjectives {
                 trace!(
@@ -774,6 +818,144 @@ impl Module {
         MODULE.with(|r| all_resources(&r.borrow().sizes))
     }
 
+    /// Re-reads all scripts from the module's directories, returning the IDs
+    /// of any scripts that were added or changed.  Scripts whose files were
+    /// removed are kept, as they may still be referenced.
+    pub fn reload_scripts() -> Vec<String> {
+        let dirs = MODULE.with(|m| m.borrow().resource_dirs.clone());
+        let mut scripts = read_to_string(&dirs, "scripts");
+        expand_include_directives(&mut scripts);
+
+        MODULE.with(|m| {
+            let mut module = m.borrow_mut();
+            let mut changed = Vec::new();
+            for (id, script) in scripts {
+                if module.scripts.get(&id) == Some(&script) {
+                    continue;
+                }
+
+                changed.push(id.to_string());
+                module.scripts.insert(id, script);
+            }
+            changed.sort();
+            changed
+        })
+    }
+
+    /// Re-reads the YAML for abilities, ability lists, classes, items, loot
+    /// lists, props, conversations, actors, and encounters from the module's
+    /// directories.  The changed resources are re-created, along with all
+    /// resources of the kinds that may reference them, so that no resource
+    /// holds an out of date definition.  Returns the kind and ID of each
+    /// resource whose YAML changed.  Resources that fail to load keep their
+    /// previous definition.
+    ///
+    /// Areas and races are not reloaded, and keep the props and encounters
+    /// they were created with.  Look up the current definitions by ID when
+    /// creating props or spawning encounters from them.
+    pub fn reload_resources() -> Result<Vec<(YamlResourceKind, String)>, Error> {
+        let dirs = MODULE.with(|m| m.borrow().resource_dirs.clone());
+        if dirs.is_empty() {
+            return invalid_data_error("No module has been loaded");
+        }
+
+        let mut yaml = YamlResourceSet::new(Path::new(&dirs[0]))?;
+        for dir in dirs.iter().skip(1) {
+            yaml.append(Path::new(dir));
+        }
+
+        MODULE.with(|m| {
+            let mut module = m.borrow_mut();
+
+            let mut changed = Vec::new();
+            let mut first_changed = None;
+            for (index, kind) in RELOAD_KINDS.iter().enumerate() {
+                let new_yaml = yaml.resources.remove(kind).unwrap_or_default();
+                let old_yaml = module.reload_yaml.remove(kind).unwrap_or_default();
+
+                let mut ids: Vec<_> = new_yaml
+                    .iter()
+                    .filter(|(id, value)| old_yaml.get(*id) != Some(value))
+                    .map(|(id, _)| id.to_string())
+                    .collect();
+                ids.sort();
+
+                if !ids.is_empty() && first_changed.is_none() {
+                    first_changed = Some(index);
+                }
+                changed.extend(ids.into_iter().map(|id| (*kind, id)));
+                module.reload_yaml.insert(*kind, new_yaml);
+            }
+
+            let first_changed = match first_changed {
+                None => return Ok(changed),
+                Some(index) => index,
+            };
+
+            for kind in RELOAD_KINDS.iter().skip(first_changed) {
+                module.reload_kind(*kind);
+            }
+
+            Ok(changed)
+        })
+    }
+
+    fn reload_kind(&mut self, kind: YamlResourceKind) {
+        let yaml = self.reload_yaml.remove(&kind).unwrap_or_default();
+        info!("Recreating {} resources of kind {:?}", yaml.len(), kind);
+
+        if kind == YamlResourceKind::Item {
+            // items with adjectives are created on demand from their base items
+            self.items.retain(|id, _| !id.contains("__ADJ__"));
+        }
+
+        for (id, value) in yaml.iter() {
+            let id = id.to_string();
+            let value = value.clone();
+            match kind {
+                YamlResourceKind::Ability => {
+                    let ability = read_builder(value).and_then(|b| Ability::new(b, self));
+                    insert_if_ok("ability", id, ability, &mut self.abilities);
+                }
+                YamlResourceKind::AbilityList => {
+                    let list = read_builder(value).and_then(|b| AbilityList::new(b, self));
+                    insert_if_ok("ability_list", id, list, &mut self.ability_lists);
+                }
+                YamlResourceKind::Class => {
+                    let class = read_builder(value).and_then(|b| Class::new(b, self));
+                    insert_if_ok("class", id, class, &mut self.classes);
+                }
+                YamlResourceKind::Item => {
+                    let item = read_builder(value).and_then(|b| Item::new(b, self));
+                    insert_if_ok("item", id, item, &mut self.items);
+                }
+                YamlResourceKind::LootList => {
+                    let list = read_builder(value).and_then(|b| LootList::new(b, self));
+                    insert_if_ok("loot list", id, list, &mut self.loot_lists);
+                }
+                YamlResourceKind::Conversation => {
+                    let convo = read_builder(value).and_then(|b| Conversation::new(b, self));
+                    insert_if_ok("conversation", id, convo, &mut self.conversations);
+                }
+                YamlResourceKind::Actor => {
+                    let actor = read_builder(value).and_then(|b| Actor::new(b, self));
+                    insert_if_ok("actor", id, actor, &mut self.actors);
+                }
+                YamlResourceKind::Prop => {
+                    let prop = read_builder(value).and_then(|b| Prop::new(b, self));
+                    insert_if_ok("prop", id, prop, &mut self.props);
+                }
+                YamlResourceKind::Encounter => {
+                    let encounter = read_builder(value).and_then(|b| Encounter::new(b, self));
+                    insert_if_ok("encounter", id, encounter, &mut self.encounters);
+                }
+                _ => (),
+            }
+        }
+
+        self.reload_yaml.insert(kind, yaml);
+    }
+
     pub fn all_scripts() -> Vec<String> {
         MODULE.with(|r| {
             let module = r.borrow();
@@ -828,6 +1010,8 @@ impl Default for Module {
     fn default() -> Module {
         Module {
             root_dir: None,
+            resource_dirs: Vec::new(),
+            reload_yaml: HashMap::new(),
             rules: None,
             campaign: None,
             abilities: HashMap::new(),
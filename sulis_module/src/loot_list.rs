@@ -18,7 +18,7 @@ use std::collections::HashMap;
 use std::io::Error;
 use std::rc::Rc;
 
-use sulis_core::util::{gen_rand, unable_to_create_error};
+use sulis_core::util::{unable_to_create_error, ReproducibleRandom};
 
 use crate::{Item, Module};
 
@@ -182,20 +182,24 @@ impl LootList {
         })
     }
 
-    pub fn generate_with_chance(&self, chance: u32) -> Vec<(u32, Rc<Item>)> {
-        let roll = gen_rand(1, 101);
+    pub fn generate_with_chance(
+        &self,
+        rand: &mut ReproducibleRandom,
+        chance: u32,
+    ) -> Vec<(u32, Rc<Item>)> {
+        let roll = rand.gen(1, 101);
         if chance >= roll {
-            self.generate_internal(0)
+            self.generate_internal(rand, 0)
         } else {
             Vec::new()
         }
     }
 
-    pub fn generate(&self) -> Vec<(u32, Rc<Item>)> {
-        self.generate_internal(0)
+    pub fn generate(&self, rand: &mut ReproducibleRandom) -> Vec<(u32, Rc<Item>)> {
+        self.generate_internal(rand, 0)
     }
 
-    fn generate_internal(&self, depth: u32) -> Vec<(u32, Rc<Item>)> {
+    fn generate_internal(&self, rand: &mut ReproducibleRandom, depth: u32) -> Vec<(u32, Rc<Item>)> {
         if depth >= MAX_DEPTH {
             warn!(
                 "Exceeded maximum sub list depth of {}.  \
@@ -205,27 +209,27 @@ impl LootList {
             return Vec::new();
         }
 
-        let num_items = self.gen_num_items();
+        let num_items = self.gen_num_items(rand);
 
         let mut items = Vec::new();
         if num_items > 0 {
             for _ in 0..num_items {
-                if let Some(item) = self.gen_item() {
+                if let Some(item) = self.gen_item(rand) {
                     items.push(item);
                 }
             }
         }
 
         for entry in self.probability_entries.iter() {
-            let roll = gen_rand(0, 100);
+            let roll = rand.gen(0, 100);
             if roll < entry.weight {
                 let quantity = if entry.quantity[0] == entry.quantity[1] {
                     entry.quantity[0]
                 } else {
-                    gen_rand(entry.quantity[0], entry.quantity[1] + 1)
+                    rand.gen(entry.quantity[0], entry.quantity[1] + 1)
                 };
 
-                let adjectives = self.gen_adjectives(entry);
+                let adjectives = self.gen_adjectives(rand, entry);
                 let item = match Module::create_get_item(&entry.id, &adjectives) {
                     None => {
                         warn!(
@@ -252,16 +256,16 @@ impl LootList {
                 Some(list) => list,
             };
 
-            let roll = gen_rand(0, 100);
+            let roll = rand.gen(0, 100);
             if roll < entry.weight {
                 let times = if entry.quantity[0] == entry.quantity[1] {
                     entry.quantity[0]
                 } else {
-                    gen_rand(entry.quantity[0], entry.quantity[1] + 1)
+                    rand.gen(entry.quantity[0], entry.quantity[1] + 1)
                 };
 
                 for _ in 0..times {
-                    let subitems = sub_list.generate_internal(depth + 1);
+                    let subitems = sub_list.generate_internal(rand, depth + 1);
                     for (quantity, item) in subitems {
                         items.push((quantity, item));
                     }
@@ -272,10 +276,10 @@ impl LootList {
         items
     }
 
-    fn gen_adjectives(&self, entry: &Entry) -> Vec<String> {
+    fn gen_adjectives(&self, rand: &mut ReproducibleRandom, entry: &Entry) -> Vec<String> {
         let mut result = Vec::new();
         if entry.adjective1_total_weight > 0 {
-            let roll = gen_rand(0, entry.adjective1_total_weight);
+            let roll = rand.gen(0, entry.adjective1_total_weight);
 
             let mut cur_weight = 0;
             for (id, weight) in entry.adjective1.iter() {
@@ -288,7 +292,7 @@ impl LootList {
         }
 
         if entry.adjective2_total_weight > 0 {
-            let roll = gen_rand(0, entry.adjective2_total_weight);
+            let roll = rand.gen(0, entry.adjective2_total_weight);
 
             let mut cur_weight = 0;
             for (id, weight) in entry.adjective2.iter() {
@@ -303,8 +307,8 @@ impl LootList {
         result
     }
 
-    fn gen_item(&self) -> Option<(u32, Rc<Item>)> {
-        let roll = gen_rand(0, self.total_entries_weight);
+    fn gen_item(&self, rand: &mut ReproducibleRandom) -> Option<(u32, Rc<Item>)> {
+        let roll = rand.gen(0, self.total_entries_weight);
 
         let mut cur_weight = 0;
         for entry in self.weighted_entries.iter() {
@@ -313,10 +317,10 @@ impl LootList {
                 let quantity = if entry.quantity[0] == entry.quantity[1] {
                     entry.quantity[0]
                 } else {
-                    gen_rand(entry.quantity[0], entry.quantity[1] + 1)
+                    rand.gen(entry.quantity[0], entry.quantity[1] + 1)
                 };
 
-                let adjectives = self.gen_adjectives(entry);
+                let adjectives = self.gen_adjectives(rand, entry);
                 let item = match Module::create_get_item(&entry.id, &adjectives) {
                     None => {
                         warn!(
@@ -334,12 +338,12 @@ impl LootList {
         None
     }
 
-    fn gen_num_items(&self) -> u32 {
+    fn gen_num_items(&self, rand: &mut ReproducibleRandom) -> u32 {
         if self.total_generate_weight == 0 {
             return 0;
         }
 
-        let roll = gen_rand(0, self.total_generate_weight);
+        let roll = rand.gen(0, self.total_generate_weight);
 
         let mut cur_gen_weight = 0;
         for generate in self.generate.iter() {
@@ -45,6 +45,9 @@ pub use self::damage::Damage;
 pub use self::damage::DamageKind;
 pub use self::damage::DamageList;
 
+pub mod difficulty;
+pub use self::difficulty::Difficulty;
+
 pub mod resistance;
 pub use self::resistance::Resistance;
 
@@ -52,8 +55,9 @@ pub mod stat_list;
 pub use self::stat_list::StatList;
 
 use crate::area::LocationKind;
+use crate::PartyAIPreset;
 use sulis_core::ui::{color, Color};
-use sulis_core::util::{gen_rand, invalid_data_error};
+use sulis_core::util::{invalid_data_error, ReproducibleRandom};
 
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
@@ -97,6 +101,15 @@ pub struct Rules {
     pub experience_factor: f32,
     pub experience_for_level: Vec<u32>,
 
+    /// The selectable difficulty presets, in the order they are shown
+    #[serde(default)]
+    pub difficulties: Vec<Difficulty>,
+
+    /// The ID of the difficulty for new games, and for saves made before
+    /// difficulties were added.  Defaults to the first difficulty.
+    #[serde(default)]
+    pub default_difficulty: Option<String>,
+
     pub combat_run_away_vis_factor: f32,
     pub loot_drop_prop: String,
 
@@ -107,6 +120,10 @@ pub struct Rules {
 
     armor_damage_reduction_cap: Vec<u32>,
 
+    /// The ID of the AI template used by party members with each AI preset
+    #[serde(default)]
+    pub party_ai: HashMap<PartyAIPreset, String>,
+
     pub rounds_per_hour: u32,
     pub hours_per_day: u32,
     pub hour_names: Vec<String>,
@@ -115,6 +132,27 @@ pub struct Rules {
 }
 
 impl Rules {
+    /// Returns the difficulty with the specified ID, or the default
+    /// difficulty if there is no ID or no difficulty with it.  If no
+    /// difficulties are defined, returns one that changes nothing.
+    pub fn difficulty(&self, id: Option<&str>) -> Difficulty {
+        let find = |id: &str| self.difficulties.iter().find(|d| d.id == id);
+
+        if let Some(id) = id {
+            match find(id) {
+                Some(difficulty) => return difficulty.clone(),
+                None => warn!("Difficulty '{}' not found, using the default", id),
+            }
+        }
+
+        self.default_difficulty
+            .as_ref()
+            .and_then(|id| find(id))
+            .or_else(|| self.difficulties.first())
+            .cloned()
+            .unwrap_or_default()
+    }
+
     pub fn canonicalize_time(&self, time: &mut Time) {
         let mut millis = time.millis;
         let mut round = time.round;
@@ -219,11 +257,30 @@ impl Rules {
     /// amount for each entry will never be zero.
     pub fn roll_damage(
         &self,
+        rand: &mut ReproducibleRandom,
         damage: &DamageList,
         armor: &Armor,
         resistance: &Resistance,
         multiplier: f32,
     ) -> Vec<(DamageKind, u32)> {
+        self.roll_damage_breakdown(rand, damage, armor, resistance, multiplier)
+            .into_iter()
+            .filter(|roll| roll.amount > 0)
+            .map(|roll| (roll.kind, roll.amount))
+            .collect()
+    }
+
+    /// Rolls damage in the same way as `roll_damage`, but returns the full
+    /// details of how each damage kind was computed, including those kinds
+    /// that were entirely absorbed.
+    pub fn roll_damage_breakdown(
+        &self,
+        rand: &mut ReproducibleRandom,
+        damage: &DamageList,
+        armor: &Armor,
+        resistance: &Resistance,
+        multiplier: f32,
+    ) -> Vec<DamageRoll> {
         debug!(
             "Rolling damage from {} to {} vs {} base armor",
             damage.min(),
@@ -239,8 +296,10 @@ impl Rules {
         for damage in damage.iter() {
             let kind = damage.kind.unwrap();
 
-            let resistance = (100 - resistance.amount(kind)) as f32 / 100.0;
-            let amount = damage.roll() as f32 * multiplier * resistance;
+            let resistance_pct = resistance.amount(kind);
+            let resistance = (100 - resistance_pct) as f32 / 100.0;
+            let rolled = damage.roll(rand);
+            let amount = rolled as f32 * multiplier * resistance;
 
             let armor = max(0, armor.amount(kind) as i32 - damage.ap as i32) as u32;
             let armor_max = self.armor_damage_reduction_cap(armor) as f32 * amount / 100.0;
@@ -250,9 +309,18 @@ impl Rules {
             let armor = if armor > amount { amount } else { armor };
 
             let amount = amount - armor;
-            if amount > 0.0 {
-                output.push((kind, amount.ceil() as u32));
-            }
+            output.push(DamageRoll {
+                kind,
+                rolled,
+                multiplier,
+                resistance: resistance_pct,
+                armor,
+                amount: if amount > 0.0 {
+                    amount.ceil() as u32
+                } else {
+                    0
+                },
+            });
         }
 
         output
@@ -279,11 +347,11 @@ impl Rules {
         self.experience_for_level[(cur_level - 1) as usize]
     }
 
-    pub fn concealment_roll(&self, concealment: i32) -> bool {
+    pub fn concealment_roll(&self, rand: &mut ReproducibleRandom, concealment: i32) -> bool {
         if concealment == 0 {
             return true;
         }
-        let roll = gen_rand(1, 101);
+        let roll = rand.gen(1, 101);
         debug!("Concealment roll: {} against {}", roll, concealment);
         roll > concealment
     }
@@ -483,6 +551,44 @@ pub enum HitKind {
     Auto,
 }
 
+/// The details of a single attack roll, as returned by
+/// `StatList::attack_roll`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttackRoll {
+    pub hit_kind: HitKind,
+
+    /// The percentile roll, not including accuracy
+    pub roll: i32,
+
+    /// The second roll used to confirm a critical hit, if one was made
+    pub crit_roll: Option<i32>,
+
+    pub accuracy: i32,
+    pub defense: i32,
+}
+
+/// The details of the damage of one kind dealt by an attack, as returned
+/// by `Rules::roll_damage_breakdown`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DamageRoll {
+    pub kind: DamageKind,
+
+    /// The base amount rolled, before any multiplier or reduction
+    pub rolled: u32,
+
+    /// The hit kind damage multiplier that was applied
+    pub multiplier: f32,
+
+    /// The resistance percentage of the target against this kind
+    pub resistance: i32,
+
+    /// The amount absorbed by the target's armor
+    pub armor: f32,
+
+    /// The final damage dealt
+    pub amount: u32,
+}
+
 impl FromStr for HitKind {
     type Err = Error;
 
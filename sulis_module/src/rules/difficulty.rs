@@ -0,0 +1,67 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use crate::rules::BonusList;
+
+/// A difficulty preset, selectable when starting a new game and changeable
+/// at any point in a campaign.  Presets are defined in the `difficulties`
+/// list of the rules.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Difficulty {
+    pub id: String,
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    /// Bonuses added to the stats of every actor in the hostile faction,
+    /// such as hit points, accuracy, and damage multipliers
+    #[serde(default)]
+    pub hostile_bonuses: BonusList,
+
+    /// Multiplies the maximum hit points of every actor in the hostile
+    /// faction, after `hostile_bonuses` are added
+    #[serde(default = "default_factor")]
+    pub hostile_hit_points_factor: f32,
+
+    /// Multiplies all experience gained, in addition to the rules
+    /// `experience_factor`
+    #[serde(default = "default_factor")]
+    pub experience_factor: f32,
+
+    /// Multiplies the chance of each actor `Reward` dropping its loot
+    #[serde(default = "default_factor")]
+    pub loot_chance_factor: f32,
+}
+
+fn default_factor() -> f32 {
+    1.0
+}
+
+impl Default for Difficulty {
+    fn default() -> Difficulty {
+        Difficulty {
+            id: String::new(),
+            name: "Normal".to_string(),
+            description: String::new(),
+            hostile_bonuses: BonusList::default(),
+            hostile_hit_points_factor: default_factor(),
+            experience_factor: default_factor(),
+            loot_chance_factor: default_factor(),
+        }
+    }
+}
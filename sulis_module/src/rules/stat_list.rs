@@ -19,12 +19,12 @@ use std::rc::Rc;
 
 use crate::rules::bonus::{AttackBonuses, AttackBuilder, Bonus, BonusKind, BonusList};
 use crate::rules::{
-    AccuracyKind, Armor, ArmorKind, Attack, AttributeList, Damage, HitKind, Resistance, Slot,
-    WeaponKind, WeaponStyle,
+    AccuracyKind, Armor, ArmorKind, Attack, AttackRoll, AttributeList, Damage, HitKind, Resistance,
+    Slot, WeaponKind, WeaponStyle,
 };
 use crate::{Actor, Module};
 use sulis_core::image::Image;
-use sulis_core::util::{gen_rand, ExtInt};
+use sulis_core::util::{ExtInt, ReproducibleRandom};
 
 #[derive(Clone)]
 pub struct StatList {
@@ -173,43 +173,56 @@ impl StatList {
 
     pub fn attack_roll(
         &self,
+        rand: &mut ReproducibleRandom,
         accuracy_kind: AccuracyKind,
         crit_immunity: bool,
         defense: i32,
         bonuses: &AttackBonuses,
-    ) -> HitKind {
+    ) -> AttackRoll {
         let accuracy = match accuracy_kind {
             AccuracyKind::Melee => self.melee_accuracy + bonuses.melee_accuracy,
             AccuracyKind::Ranged => self.ranged_accuracy + bonuses.ranged_accuracy,
             AccuracyKind::Spell => self.spell_accuracy + bonuses.spell_accuracy,
         };
-        let roll = gen_rand(1, 101);
+        let roll = rand.gen(1, 101);
         debug!(
             "Attack roll: {} with accuracy {} against {}",
             roll, accuracy, defense
         );
 
+        let mut attack_roll = AttackRoll {
+            hit_kind: HitKind::Miss,
+            roll,
+            crit_roll: None,
+            accuracy,
+            defense,
+        };
+
         if roll + accuracy < defense {
-            return HitKind::Miss;
+            return attack_roll;
         }
 
         let result = roll + accuracy - defense;
 
-        if !crit_immunity && (100 - roll) < self.crit_chance + bonuses.crit_chance {
-            let roll2 = gen_rand(1, 101);
-            let result2 = roll2 + accuracy - defense;
-            if result2 > self.graze_threshold + bonuses.graze_threshold {
-                HitKind::Crit
-            } else {
+        attack_roll.hit_kind =
+            if !crit_immunity && (100 - roll) < self.crit_chance + bonuses.crit_chance {
+                let roll2 = rand.gen(1, 101);
+                attack_roll.crit_roll = Some(roll2);
+                let result2 = roll2 + accuracy - defense;
+                if result2 > self.graze_threshold + bonuses.graze_threshold {
+                    HitKind::Crit
+                } else {
+                    HitKind::Hit
+                }
+            } else if result > self.hit_threshold + bonuses.hit_threshold {
                 HitKind::Hit
-            }
-        } else if result > self.hit_threshold + bonuses.hit_threshold {
-            HitKind::Hit
-        } else if result > self.graze_threshold + bonuses.graze_threshold {
-            HitKind::Graze
-        } else {
-            HitKind::Miss
-        }
+            } else if result > self.graze_threshold + bonuses.graze_threshold {
+                HitKind::Graze
+            } else {
+                HitKind::Miss
+            };
+
+        attack_roll
     }
 
     pub fn attack_is_melee(&self) -> bool {
@@ -0,0 +1,110 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use sulis_core::util::Point;
+
+/// The out of combat behavior of an actor over the course of each in game
+/// day.  May be set on an actor, or on a single actor placed in an area,
+/// which takes precedence.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Schedule {
+    /// The activities making up the day.  Each activity starts at its `hour`
+    /// and lasts until the next activity starts.  Before the earliest
+    /// activity's hour, the latest activity of the previous day continues.
+    #[serde(default)]
+    pub activities: Vec<Activity>,
+
+    /// Animations played at random while the actor is not walking
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub idle: Vec<IdleAnimation>,
+
+    /// The average time in milliseconds between idle animations or
+    /// wandering moves
+    #[serde(default = "default_idle_interval")]
+    pub idle_interval: u32,
+}
+
+fn default_idle_interval() -> u32 {
+    6000
+}
+
+impl Schedule {
+    /// Returns the index of the activity in progress at the specified hour
+    /// of the day, or `None` if there are no activities
+    pub fn activity_index(&self, hour: u32) -> Option<usize> {
+        let started = self
+            .activities
+            .iter()
+            .enumerate()
+            .filter(|(_, activity)| activity.hour <= hour)
+            .max_by_key(|(_, activity)| activity.hour);
+
+        let (index, _) = match started {
+            Some(started) => started,
+            None => self
+                .activities
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, activity)| activity.hour)?,
+        };
+        Some(index)
+    }
+}
+
+/// Where an actor goes and what it does for part of the day.  An activity
+/// with several waypoints is a patrol route, walked in order and looping
+/// back to the first waypoint after the last.  With a single waypoint, the
+/// actor walks there and stays, and with none, it stays where it is.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Activity {
+    /// The hour of the day this activity starts
+    #[serde(default)]
+    pub hour: u32,
+
+    #[serde(default)]
+    pub waypoints: Vec<Waypoint>,
+
+    /// The distance the actor wanders from its current waypoint, or from
+    /// its starting location, while waiting there
+    #[serde(default)]
+    pub wander_radius: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Waypoint {
+    pub location: Point,
+
+    /// The time in milliseconds spent at this waypoint before walking to
+    /// the next one on a patrol route
+    #[serde(default)]
+    pub wait_millis: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub enum IdleAnimation {
+    /// A small hop in place
+    Hop,
+
+    /// A quick step to one side and back
+    Shuffle,
+
+    /// Slowly growing and shrinking in size
+    Breathe,
+}
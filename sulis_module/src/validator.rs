@@ -0,0 +1,500 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Offline validation of a loaded module.  Each resource's YAML is walked
+//! looking for IDs that do not refer to any resource in the module, and each
+//! problem is reported with the file and YAML path it was found at.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use sulis_core::resource::yaml_resource_set::{DIRECTORY_VAL_STR, FILE_VAL_STR};
+use sulis_core::resource::{ResourceSet, YamlResourceKind};
+use sulis_core::serde_yaml::Value;
+
+use crate::Module;
+
+/// A single problem found by `validate`
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+    /// The file, or files when merged from multiple directories, that
+    /// define the resource containing the problem
+    pub file: String,
+
+    /// The path within the resource's YAML, such as `nodes.main.responses[1].to`
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}: {}", self.file, self.message)
+        } else {
+            write!(f, "{}: {}: {}", self.file, self.path, self.message)
+        }
+    }
+}
+
+/// Validates the currently loaded module against the YAML it was loaded from.
+/// This should be a copy of the `YamlResourceSet` resources passed to
+/// `Module::load_resources`.  Reports resources that failed to load, and
+/// dangling references to scripts, conversations and conversation nodes,
+/// quests and quest entries, actors, props, encounters, loot lists, areas,
+/// and images.
+///
+/// `has_func` is called with a script ID and function name, and should return
+/// whether that script defines the function.  It is used to check the script
+/// functions named by triggers, abilities, items, and AI templates.
+pub fn validate(
+    resources: &HashMap<YamlResourceKind, HashMap<String, Value>>,
+    has_func: &dyn Fn(&str, &str) -> bool,
+) -> Vec<ValidationError> {
+    let mut kinds: Vec<_> = resources.iter().collect();
+    kinds.sort_by_key(|(kind, _)| format!("{:?}", kind));
+
+    let mut errors = Vec::new();
+    for (kind, map) in kinds {
+        let mut ids: Vec<_> = map.keys().collect();
+        ids.sort();
+
+        for id in ids {
+            let mut validator = Validator {
+                file: files(&map[id]),
+                errors: &mut errors,
+                has_func,
+            };
+            validator.check_resource(*kind, id, &map[id]);
+        }
+    }
+
+    errors
+}
+
+fn files(value: &Value) -> String {
+    let files = match value.get(FILE_VAL_STR) {
+        Some(Value::Sequence(files)) => files,
+        _ => return "<unknown file>".to_string(),
+    };
+
+    let files: Vec<&str> = files.iter().filter_map(|file| file.as_str()).collect();
+    files.join(", ")
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn key_str(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => "?".to_string(),
+    }
+}
+
+struct Validator<'a> {
+    file: String,
+    errors: &'a mut Vec<ValidationError>,
+    has_func: &'a dyn Fn(&str, &str) -> bool,
+}
+
+impl<'a> Validator<'a> {
+    fn error(&mut self, path: &str, message: String) {
+        self.errors.push(ValidationError {
+            file: self.file.to_string(),
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn check_resource(&mut self, kind: YamlResourceKind, id: &str, value: &Value) {
+        use self::YamlResourceKind::*;
+        let loaded = match kind {
+            Ability => Module::ability(id).is_some(),
+            AbilityList => Module::ability_list(id).is_some(),
+            Actor => Module::actor(id).is_some(),
+            AiTemplate => Module::ai_template(id).is_some(),
+            Area => Module::area(id).is_some(),
+            Class => Module::class(id).is_some(),
+            Conversation => Module::conversation(id).is_some(),
+            Cutscene => Module::cutscene(id).is_some(),
+            Encounter => Module::encounter(id).is_some(),
+            Item => Module::item(id).is_some(),
+            ItemAdjective => Module::item_adjective(id).is_some(),
+            LootList => Module::loot_list(id).is_some(),
+            Prop => Module::prop(id).is_some(),
+            Quest => Module::quest(id).is_some(),
+            Race => Module::race(id).is_some(),
+            Size => Module::size(id).is_some(),
+            Generator => Module::generator(id).is_some(),
+            _ => true,
+        };
+
+        if !loaded {
+            let kind = format!("{:?}", kind);
+            self.error("", format!("{} '{}' failed to load", kind, id));
+        }
+
+        match kind {
+            Ability => {
+                self.check_entry_point(value, "active");
+                self.check_image_field("", value, "icon");
+            }
+            Actor => self.check_actor(value),
+            AiTemplate => self.check_ai_template(value),
+            Area => self.check_area(value),
+            Conversation => self.check_conversation(value),
+            Encounter => self.check_encounter(value),
+            Item => self.check_item(value),
+            ItemAdjective => self.check_image_field("", value, "item_status_icon"),
+            Prop => {
+                self.check_image_field("", value, "icon");
+                self.check_image_field("", value, "image");
+            }
+            Race => self.check_race(value),
+            TopLevel => self.check_campaign(value),
+            _ => (),
+        }
+
+        self.check_value("", value);
+    }
+
+    /// Recursively checks for `OnTrigger`s and scripts referenced anywhere
+    /// in the resource
+    fn check_value(&mut self, path: &str, value: &Value) {
+        match value {
+            Value::Mapping(map) => {
+                for (key, value) in map.iter() {
+                    let key = key_str(key);
+                    if key == FILE_VAL_STR || key == DIRECTORY_VAL_STR {
+                        continue;
+                    }
+
+                    let path = join(path, &key);
+                    self.check_key(&path, &key, value);
+                    self.check_value(&path, value);
+                }
+            }
+            Value::Sequence(seq) => {
+                for (index, value) in seq.iter().enumerate() {
+                    self.check_value(&format!("{}[{}]", path, index), value);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn check_key(&mut self, path: &str, key: &str, value: &Value) {
+        match key {
+            "fire_script"
+            | "on_accept"
+            | "on_tick_script"
+            | "on_round_elapsed_script"
+            | "on_party_death_script" => self.check_script_data(path, value),
+            "script" => {
+                if let Value::String(id) = value {
+                    self.check_ref(path, "script", id, Module::script(id).is_some());
+                }
+            }
+            "quest_state" | "not_quest_state" => self.check_quest_state(path, value),
+            "start_conversation" => self.check_str(path, value, "conversation", |id| {
+                Module::conversation(id).is_some()
+            }),
+            "show_cutscene" => {
+                self.check_str(path, value, "cutscene", |id| Module::cutscene(id).is_some())
+            }
+            "show_merchant" => self.check_field(path, value, "loot_list", "loot list", |id| {
+                Module::loot_list(id).is_some()
+            }),
+            "party_item" => self.check_str(path, value, "item", |id| Module::item(id).is_some()),
+            "player_ability" => {
+                self.check_str(path, value, "ability", |id| Module::ability(id).is_some())
+            }
+            "projectile" => self.check_image(path, value),
+            _ => (),
+        }
+    }
+
+    fn check_ref(&mut self, path: &str, kind: &str, id: &str, exists: bool) {
+        if !exists {
+            self.error(path, format!("No {} with ID '{}'", kind, id));
+        }
+    }
+
+    fn check_str<F: Fn(&str) -> bool>(&mut self, path: &str, value: &Value, kind: &str, f: F) {
+        if let Value::String(id) = value {
+            self.check_ref(path, kind, id, f(id));
+        }
+    }
+
+    fn check_field<F: Fn(&str) -> bool>(
+        &mut self,
+        path: &str,
+        value: &Value,
+        field: &str,
+        kind: &str,
+        f: F,
+    ) {
+        if let Some(field_value) = value.get(field) {
+            self.check_str(&join(path, field), field_value, kind, f);
+        }
+    }
+
+    fn check_image(&mut self, path: &str, value: &Value) {
+        self.check_str(path, value, "image", |id| ResourceSet::image(id).is_some());
+    }
+
+    fn check_image_field(&mut self, path: &str, value: &Value, field: &str) {
+        self.check_field(path, value, field, "image", |id| {
+            ResourceSet::image(id).is_some()
+        });
+    }
+
+    /// Checks each image in a mapping or sequence of image IDs, such as
+    /// the image layers of an actor
+    fn check_images(&mut self, path: &str, value: Option<&Value>) {
+        match value {
+            Some(Value::Mapping(map)) => {
+                for (key, image) in map.iter() {
+                    self.check_image(&join(path, &key_str(key)), image);
+                }
+            }
+            Some(Value::Sequence(seq)) => {
+                for (index, image) in seq.iter().enumerate() {
+                    self.check_image(&format!("{}[{}]", path, index), image);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn check_seq_ids<F: Fn(&str) -> bool>(&mut self, value: &Value, key: &str, kind: &str, f: F) {
+        let seq = match value.get(key) {
+            Some(Value::Sequence(seq)) => seq,
+            _ => return,
+        };
+
+        for (index, entry) in seq.iter().enumerate() {
+            let path = format!("{}[{}]", key, index);
+            self.check_field(&path, entry, "id", kind, &f);
+        }
+    }
+
+    fn check_script_data(&mut self, path: &str, value: &Value) {
+        self.check_field(path, value, "id", "script", |id| {
+            Module::script(id).is_some()
+        });
+
+        if let (Some(Value::String(id)), Some(Value::String(func))) =
+            (value.get("id"), value.get("func"))
+        {
+            self.check_func(&join(path, "func"), id, func);
+        }
+    }
+
+    /// Checks that the specified script defines the function.  Missing
+    /// scripts are reported elsewhere
+    fn check_func(&mut self, path: &str, script: &str, func: &str) {
+        if Module::script(script).is_none() || (self.has_func)(script, func) {
+            return;
+        }
+
+        let message = format!("Script '{}' does not define function '{}'", script, func);
+        self.error(path, message);
+    }
+
+    /// Checks the `on_activate` entry point of an ability's `active` or an
+    /// item's `usable` script
+    fn check_entry_point(&mut self, value: &Value, key: &str) {
+        if let Some(Value::String(script)) = value.get(key).and_then(|v| v.get("script")) {
+            self.check_func(&join(key, "script"), script, "on_activate");
+        }
+    }
+
+    fn check_ai_template(&mut self, value: &Value) {
+        let script = match value.get("script") {
+            Some(Value::String(script)) => script,
+            _ => return,
+        };
+
+        self.check_func("script", script, "ai_action");
+
+        if let Some(Value::Mapping(hooks)) = value.get("hooks") {
+            for (kind, func) in hooks.iter() {
+                if let Value::String(func) = func {
+                    let path = join("hooks", &key_str(kind));
+                    self.check_func(&path, script, func);
+                }
+            }
+        }
+    }
+
+    fn check_quest_state(&mut self, path: &str, value: &Value) {
+        let quest_id = match value.get("quest") {
+            Some(Value::String(id)) => id,
+            _ => return,
+        };
+
+        let quest = match Module::quest(quest_id) {
+            None => {
+                self.check_ref(&join(path, "quest"), "quest", quest_id, false);
+                return;
+            }
+            Some(quest) => quest,
+        };
+
+        if let Some(Value::String(entry)) = value.get("entry") {
+            if !quest.entries.contains_key(entry) {
+                let message = format!("No entry '{}' in quest '{}'", entry, quest_id);
+                self.error(&join(path, "entry"), message);
+            }
+        }
+    }
+
+    fn check_actor(&mut self, value: &Value) {
+        self.check_field("", value, "conversation", "conversation", |id| {
+            Module::conversation(id).is_some()
+        });
+        self.check_field("", value, "ai", "AI template", |id| {
+            Module::ai_template(id).is_some()
+        });
+        self.check_field("", value, "loot", "loot list", |id| {
+            Module::loot_list(id).is_some()
+        });
+        self.check_field("", value, "race", "race", |id| Module::race(id).is_some());
+        self.check_image_field("", value, "portrait");
+        self.check_images("images", value.get("images"));
+
+        if let Some(Value::Mapping(levels)) = value.get("levels") {
+            for (class, _) in levels.iter() {
+                let class = key_str(class);
+                let path = join("levels", &class);
+                self.check_ref(&path, "class", &class, Module::class(&class).is_some());
+            }
+        }
+
+        if let Some(Value::Sequence(abilities)) = value.get("abilities") {
+            for (index, ability) in abilities.iter().enumerate() {
+                let path = format!("abilities[{}]", index);
+                self.check_str(&path, ability, "ability", |id| {
+                    Module::ability(id).is_some()
+                });
+            }
+        }
+    }
+
+    fn check_item(&mut self, value: &Value) {
+        self.check_entry_point(value, "usable");
+        self.check_image_field("", value, "icon");
+        self.check_images("image", value.get("image"));
+    }
+
+    fn check_race(&mut self, value: &Value) {
+        for key in ["hair_selections", "beard_selections", "portrait_selections"].iter() {
+            self.check_images(key, value.get(key));
+        }
+
+        for key in ["default_images", "editor_creator_images"].iter() {
+            if let Some(Value::Mapping(map)) = value.get(key) {
+                for (sub_key, images) in map.iter() {
+                    self.check_images(&join(key, &key_str(sub_key)), Some(images));
+                }
+            }
+        }
+    }
+
+    fn check_area(&mut self, value: &Value) {
+        self.check_seq_ids(value, "actors", "actor", |id| Module::actor(id).is_some());
+        self.check_seq_ids(value, "props", "prop", |id| Module::prop(id).is_some());
+        self.check_seq_ids(value, "encounters", "encounter", |id| {
+            Module::encounter(id).is_some()
+        });
+
+        let transitions = match value.get("transitions") {
+            Some(Value::Sequence(seq)) => seq,
+            _ => return,
+        };
+
+        for (index, transition) in transitions.iter().enumerate() {
+            let path = format!("transitions[{}]", index);
+            self.check_image_field(&path, transition, "image_display");
+
+            if let Some(to) = transition.get("to").and_then(|to| to.get("Area")) {
+                let path = format!("transitions[{}].to.Area", index);
+                self.check_field(&path, to, "id", "area", |id| Module::area(id).is_some());
+            }
+        }
+    }
+
+    fn check_conversation(&mut self, value: &Value) {
+        let nodes = match value.get("nodes") {
+            Some(Value::Mapping(nodes)) => nodes,
+            _ => return,
+        };
+        let has_node = |id: &str| nodes.contains_key(&Value::String(id.to_string()));
+
+        self.check_seq_ids(value, "initial_nodes", "node", has_node);
+
+        for (node_id, node) in nodes.iter() {
+            let node_path = join("nodes", &key_str(node_id));
+            let responses = match node.get("responses") {
+                Some(Value::Sequence(responses)) => responses,
+                _ => continue,
+            };
+
+            for (index, response) in responses.iter().enumerate() {
+                let path = format!("{}.responses[{}]", node_path, index);
+                self.check_field(&path, response, "to", "node", has_node);
+            }
+        }
+    }
+
+    fn check_encounter(&mut self, value: &Value) {
+        self.check_seq_ids(value, "entries", "actor", |id| Module::actor(id).is_some());
+    }
+
+    fn check_campaign(&mut self, value: &Value) {
+        if value.get("starting_area").is_none() {
+            return;
+        }
+
+        self.check_field("", value, "starting_area", "area", |id| {
+            Module::area(id).is_some()
+        });
+        self.check_field("", value, "backstory_conversation", "conversation", |id| {
+            Module::conversation(id).is_some()
+        });
+
+        let locations = match value.get("world_map").and_then(|map| map.get("locations")) {
+            Some(Value::Mapping(locations)) => locations,
+            _ => return,
+        };
+
+        for (id, location) in locations.iter() {
+            let path = join("world_map.locations", &key_str(id));
+            self.check_field(&path, location, "linked_area", "area", |id| {
+                Module::area(id).is_some()
+            });
+            self.check_image_field(&path, location, "icon");
+        }
+    }
+}
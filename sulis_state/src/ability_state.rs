@@ -48,6 +48,16 @@ impl AbilityState {
         }
     }
 
+    /// Replaces the ability with a new definition of it, such as after
+    /// `Module::reload_resources`.  The ability must be active
+    pub(crate) fn set_ability(&mut self, ability: Rc<Ability>) {
+        if let Some(ref active) = ability.active {
+            self.group = active.group.name();
+            self.combat_only = active.combat_only;
+        }
+        self.ability = ability;
+    }
+
     pub fn update(&mut self, millis_elapsed: u32) {
         let cur_mod = self.cur_duration / ROUND_TIME_MILLIS;
         self.cur_duration += millis_elapsed;
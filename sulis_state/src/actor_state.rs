@@ -20,6 +20,7 @@ use std::collections::HashMap;
 use std::io::Error;
 use std::rc::Rc;
 
+use crate::combat_log::{CombatLog, CombatLogKind};
 use crate::save_state::ActorSaveState;
 use crate::{
     AbilityState, ChangeListenerList, Effect, EntityState, GameState, Inventory, ItemState, PStats,
@@ -28,8 +29,8 @@ use sulis_core::image::{Image, LayeredImage};
 use sulis_core::io::GraphicsRenderer;
 use sulis_core::util::{invalid_data_error, ExtInt};
 use sulis_module::{
-    AccuracyKind, Attack, AttackKind, BonusList, DamageKind, HitFlags, HitKind, ItemKind,
-    QuickSlot, Slot, StatList, WeaponStyle,
+    AccuracyKind, Attack, AttackKind, AttackRoll, BonusList, DamageKind, DamageRoll, HitFlags,
+    HitKind, ItemKind, QuickSlot, Slot, StatList, WeaponStyle,
 };
 use sulis_module::{Ability, Actor, ActorBuilder, Faction, ImageLayer, Module};
 
@@ -264,7 +265,13 @@ impl ActorState {
     }
 
     pub fn set_faction(&mut self, faction: Faction) {
+        if self.p_stats.faction == faction {
+            return;
+        }
+
         self.p_stats.faction = faction;
+        // the difficulty bonuses depend on the faction
+        self.compute_stats();
     }
 
     pub fn clone_p_stats(&self) -> PStats {
@@ -471,7 +478,33 @@ impl ActorState {
     }
 
     pub fn replace_actor(&mut self, new_actor: Actor) {
-        self.actor = Rc::new(new_actor);
+        self.set_actor(Rc::new(new_actor));
+    }
+
+    /// Replaces the actor, and all items and abilities, with their current
+    /// definitions from the module, such as after `Module::reload_resources`
+    pub(crate) fn reload_resources(&mut self, actor: Rc<Actor>) {
+        self.inventory.reload_items();
+
+        self.ability_states
+            .retain(|id, state| match Module::ability(id) {
+                None => true,
+                Some(ability) => {
+                    if ability.active.is_none() {
+                        return false;
+                    }
+                    state.set_ability(ability);
+                    true
+                }
+            });
+
+        self.set_actor(actor);
+        self.texture_cache_invalid = true;
+        self.listeners.notify(self);
+    }
+
+    fn set_actor(&mut self, actor: Rc<Actor>) {
+        self.actor = actor;
 
         for ability in self.actor.abilities.iter() {
             let ability = &ability.ability;
@@ -567,7 +600,24 @@ impl ActorState {
         parent.borrow().actor.stats.hidden && !target.borrow().actor.stats.sneak_attack_immunity
     }
 
-    fn is_flanking(parent: &Rc<RefCell<EntityState>>, target: &Rc<RefCell<EntityState>>) -> bool {
+    pub(crate) fn is_flanking(
+        parent: &Rc<RefCell<EntityState>>,
+        target: &Rc<RefCell<EntityState>>,
+    ) -> bool {
+        let p_parent = (
+            parent.borrow().center_x_f32(),
+            parent.borrow().center_y_f32(),
+        );
+        ActorState::is_flanking_from(parent, p_parent, target)
+    }
+
+    /// Returns true if the parent would be flanking the target when
+    /// attacking with its center at `p_parent`, false otherwise
+    pub(crate) fn is_flanking_from(
+        parent: &Rc<RefCell<EntityState>>,
+        p_parent: (f32, f32),
+        target: &Rc<RefCell<EntityState>>,
+    ) -> bool {
         if target.borrow().actor.stats.flanked_immunity {
             return false;
         }
@@ -611,10 +661,6 @@ impl ActorState {
                 target.borrow().center_x_f32(),
                 target.borrow().center_y_f32(),
             );
-            let p_parent = (
-                parent.borrow().center_x_f32(),
-                parent.borrow().center_y_f32(),
-            );
             let p_other = (entity.center_x_f32(), entity.center_y_f32());
 
             let p1 = (p_target.0 - p_parent.0, p_target.1 - p_parent.1);
@@ -728,16 +774,14 @@ impl ActorState {
                 - parent.borrow().actor.stats.concealment_ignore,
         );
 
-        if !rules.concealment_roll(concealment) {
+        if !GameState::with_random(|rand| rules.concealment_roll(rand, concealment)) {
             debug!("Concealment miss");
-            return (
-                HitKind::Miss,
-                HitFlags {
-                    concealment: true,
-                    ..Default::default()
-                },
-                Vec::new(),
-            );
+            let hit_flags = HitFlags {
+                concealment: true,
+                ..Default::default()
+            };
+            log_attack(parent, target, None, HitKind::Miss, hit_flags, Vec::new());
+            return (HitKind::Miss, hit_flags, Vec::new());
         }
 
         let (accuracy_kind, defense) = {
@@ -771,13 +815,22 @@ impl ActorState {
             concealment: false,
         };
 
-        let (hit_kind, damage_multiplier) = {
+        let (attack_roll, damage_multiplier) = {
             let parent_stats = &parent.borrow().actor.stats;
-            let hit_kind =
-                parent_stats.attack_roll(accuracy_kind, crit_immunity, defense, &attack.bonuses);
-            let damage_multiplier = match hit_kind {
+            let attack_roll = GameState::with_random(|rand| {
+                parent_stats.attack_roll(
+                    rand,
+                    accuracy_kind,
+                    crit_immunity,
+                    defense,
+                    &attack.bonuses,
+                )
+            });
+            let damage_multiplier = match attack_roll.hit_kind {
                 HitKind::Miss => {
                     debug!("Miss");
+                    let roll = Some(attack_roll);
+                    log_attack(parent, target, roll, HitKind::Miss, hit_flags, Vec::new());
                     return (HitKind::Miss, hit_flags, Vec::new());
                 }
                 HitKind::Graze => parent_stats.graze_multiplier + attack.bonuses.graze_multiplier,
@@ -785,14 +838,36 @@ impl ActorState {
                 HitKind::Crit => parent_stats.crit_multiplier + attack.bonuses.crit_multiplier,
                 HitKind::Auto => panic!(),
             };
-            (hit_kind, damage_multiplier)
+            (attack_roll, damage_multiplier)
         };
+        let hit_kind = attack_roll.hit_kind;
 
-        let damage = {
+        let damage_rolls = {
             let target = &target.borrow().actor.stats;
             let damage = &attack.damage;
-            rules.roll_damage(damage, &target.armor, &target.resistance, damage_multiplier)
+            GameState::with_random(|rand| {
+                rules.roll_damage_breakdown(
+                    rand,
+                    damage,
+                    &target.armor,
+                    &target.resistance,
+                    damage_multiplier,
+                )
+            })
         };
+        let damage: Vec<_> = damage_rolls
+            .iter()
+            .filter(|roll| roll.amount > 0)
+            .map(|roll| (roll.kind, roll.amount))
+            .collect();
+        log_attack(
+            parent,
+            target,
+            Some(attack_roll),
+            hit_kind,
+            hit_flags,
+            damage_rolls,
+        );
 
         debug!("{:?}. {:?} damage", hit_kind, damage);
 
@@ -926,7 +1001,9 @@ impl ActorState {
         };
 
         trace!("Checking for loot drop.");
-        let items = loot.generate_with_chance(reward.loot_chance);
+        let chance = reward.loot_chance as f32 * GameState::difficulty().loot_chance_factor;
+        let items =
+            GameState::with_random(|rand| loot.generate_with_chance(rand, chance.round() as u32));
         if items.is_empty() {
             return;
         }
@@ -1153,6 +1230,12 @@ impl ActorState {
             self.stats.add(bonuses);
         }
 
+        let difficulty = GameState::difficulty();
+        let is_hostile = self.faction() == Faction::Hostile;
+        if is_hostile {
+            self.stats.add(&difficulty.hostile_bonuses);
+        }
+
         let mut equipped_armor = HashMap::new();
         for slot in Slot::iter() {
             if let Some(ref item_state) = self.inventory.equipped(*slot) {
@@ -1176,8 +1259,33 @@ impl ActorState {
             is_threatened,
         );
 
+        if is_hostile {
+            // scale rather than subtract, so weak hostiles never end up at
+            // or below zero hit points
+            let max_hp = self.stats.max_hp as f32 * difficulty.hostile_hit_points_factor;
+            self.stats.max_hp = (max_hp.round() as i32).max(1);
+        }
+
         self.p_stats.recompute_level_up(&self.actor);
 
         self.listeners.notify(&self);
     }
 }
+
+fn log_attack(
+    parent: &Rc<RefCell<EntityState>>,
+    target: &Rc<RefCell<EntityState>>,
+    roll: Option<AttackRoll>,
+    hit_kind: HitKind,
+    flags: HitFlags,
+    damage: Vec<DamageRoll>,
+) {
+    CombatLog::push(CombatLogKind::Attack {
+        attacker: parent.borrow().actor.actor.name.to_string(),
+        defender: target.borrow().actor.actor.name.to_string(),
+        roll,
+        hit_kind,
+        flags,
+        damage,
+    });
+}
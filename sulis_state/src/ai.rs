@@ -14,6 +14,18 @@
 //  You should have received a copy of the GNU General Public License
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
+mod group;
+pub use self::group::GroupState;
+
+mod position;
+pub use self::position::{evaluate_positions, score_position};
+
+mod schedule;
+pub use self::schedule::{update_schedules, ScheduleState};
+
+mod utility;
+pub use self::utility::{UtilityAI, UtilityAction};
+
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -41,7 +53,8 @@ impl AI {
             return;
         }
 
-        if entity.borrow().is_party_member() {
+        let party_member = entity.borrow().is_party_member();
+        if party_member && entity.borrow().ai_template().is_none() {
             self.ai = None;
             return;
         }
@@ -100,6 +113,7 @@ const MAX_ACTIONS: u32 = 10;
 struct EntityAI {
     entity: Rc<RefCell<EntityState>>,
     actions_taken_this_turn: u32,
+    utility: UtilityAI,
 }
 
 impl EntityAI {
@@ -107,6 +121,7 @@ impl EntityAI {
         EntityAI {
             entity: Rc::clone(entity),
             actions_taken_this_turn: 0,
+            utility: UtilityAI::new(),
         }
     }
 
@@ -125,12 +140,22 @@ impl EntityAI {
             return State::End;
         }
 
-        if self.entity.borrow().actor.actor.ai.is_none() {
-            return State::End;
-        }
+        let ai = match self.entity.borrow().ai_template() {
+            None => return State::End,
+            Some(ai) => ai,
+        };
 
         self.actions_taken_this_turn += 1;
-
-        Script::ai(&self.entity, "ai_action")
+        GroupState::update(&self.entity);
+
+        match &ai.utility {
+            None => Script::ai(&self.entity, "ai_action"),
+            Some(utility) => self.utility.run(
+                &self.entity,
+                &ai.script,
+                utility.script_override.as_deref(),
+                &utility.weights,
+            ),
+        }
     }
 }
@@ -0,0 +1,271 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Coordinated tactics for the AI group of each spawned encounter.  Group
+//! members share a focus target, spread out against area of effect
+//! abilities, and act according to their roles.  Depending on the
+//! encounter's `GroupTactics`, a losing group calls reinforcements from
+//! its encounter, or retreats together.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::turn_manager::EncounterRef;
+use crate::{EntityState, GameState};
+use sulis_module::ability::{AIGroup, AIKind};
+use sulis_module::{AIRole, GroupTactics, PositionWeights};
+
+/// The current tactical state of an entity's group
+pub struct GroupState {
+    /// The other living members of the group
+    pub members: Vec<Rc<RefCell<EntityState>>>,
+
+    /// The hostile the group is focusing on, if any
+    pub focus: Option<Rc<RefCell<EntityState>>>,
+
+    /// Whether the group is retreating
+    pub retreating: bool,
+
+    /// Whether members should keep their distance from each other
+    pub spread: bool,
+}
+
+impl GroupState {
+    /// Returns the role of the entity, from its AI template or inferred from
+    /// its attacks
+    pub fn role(entity: &EntityState) -> AIRole {
+        let role = entity.ai_template().and_then(|ai| ai.role);
+        match role {
+            Some(role) => role,
+            None if entity.actor.stats.attack_is_ranged() => AIRole::Ranged,
+            None => AIRole::Tank,
+        }
+    }
+
+    /// Adjusts the position weights for the specified role.  Tanks care less
+    /// about threat and more about holding chokepoints, while ranged attackers
+    /// and healers avoid threatened positions.
+    pub fn role_position_weights(role: AIRole, weights: &PositionWeights) -> PositionWeights {
+        let mut weights = weights.clone();
+        match role {
+            AIRole::Tank => {
+                weights.threat *= 0.5;
+                weights.chokepoint *= 2.0;
+            }
+            AIRole::Ranged | AIRole::Healer => weights.threat *= 2.0,
+        }
+        weights
+    }
+
+    /// Updates the group of the parent, if it has one.  Chooses the group's
+    /// focus target, and calls reinforcements or starts a retreat if the group
+    /// is losing.  Called each time a group member is about to act.
+    pub fn update(parent: &Rc<RefCell<EntityState>>) {
+        let group = match parent.borrow().ai_group() {
+            None => return,
+            Some(group) => group,
+        };
+
+        let mgr = GameState::turn_manager();
+        let enc_ref = match mgr.borrow().ai_groups.get(&group) {
+            None => return,
+            Some(enc_ref) => enc_ref.clone(),
+        };
+
+        let tactics = match tactics(&enc_ref) {
+            None => return,
+            Some(tactics) => tactics,
+        };
+
+        let members = members(group, &enc_ref.area_id);
+        let (hp, max_hp) = members.iter().fold((0, 0), |(hp, max_hp), member| {
+            let member = member.borrow();
+            (hp + member.actor.hp(), max_hp + member.actor.stats.max_hp)
+        });
+        let max_hp = max_hp.max(enc_ref.max_hp);
+        let strength = if max_hp > 0 {
+            hp as f32 / max_hp as f32
+        } else {
+            1.0
+        };
+
+        let reinforce = strength < tactics.reinforce_below
+            && enc_ref.reinforcements_called < tactics.reinforcements;
+        let retreating = !reinforce && strength < tactics.retreat_below;
+        let focus = if tactics.focus {
+            choose_focus(parent, &members, enc_ref.focus)
+        } else {
+            None
+        };
+
+        {
+            let mut mgr = mgr.borrow_mut();
+            let enc_ref = match mgr.ai_group_mut(group) {
+                None => return,
+                Some(enc_ref) => enc_ref,
+            };
+            enc_ref.max_hp = max_hp;
+            enc_ref.focus = focus;
+            if reinforce {
+                enc_ref.reinforcements_called += 1;
+            }
+            if retreating && !enc_ref.retreating {
+                info!("AI group {} is retreating at strength {}", group, strength);
+            }
+            enc_ref.retreating = retreating;
+        }
+
+        if reinforce {
+            call_reinforcements(group, &enc_ref);
+        }
+    }
+
+    /// Returns the current state of the parent's group, or `None` if it is not
+    /// in a group
+    pub fn for_entity(parent: &Rc<RefCell<EntityState>>) -> Option<GroupState> {
+        let group = parent.borrow().ai_group()?;
+
+        let mgr = GameState::turn_manager();
+        let enc_ref = mgr.borrow().ai_groups.get(&group)?.clone();
+        let tactics = tactics(&enc_ref)?;
+
+        let members = members(group, &enc_ref.area_id)
+            .into_iter()
+            .filter(|member| !Rc::ptr_eq(member, parent))
+            .collect();
+
+        let focus = enc_ref
+            .focus
+            .and_then(|index| mgr.borrow().entity_checked(index))
+            .filter(|focus| !focus.borrow().actor.is_dead());
+
+        let spread = tactics.spread && hostiles_have_area_effects(parent);
+
+        Some(GroupState {
+            members,
+            focus,
+            retreating: enc_ref.retreating,
+            spread,
+        })
+    }
+}
+
+fn tactics(enc_ref: &EncounterRef) -> Option<GroupTactics> {
+    let area = GameState::get_area_state(&enc_ref.area_id)?;
+    let encounter = area.borrow().encounter(enc_ref.encounter_index)?;
+    Some(encounter.tactics.clone())
+}
+
+fn members(group: usize, area_id: &str) -> Vec<Rc<RefCell<EntityState>>> {
+    let mgr = GameState::turn_manager();
+    let mgr = mgr.borrow();
+    mgr.entity_iter()
+        .filter(|entity| {
+            let entity = entity.borrow();
+            entity.ai_group() == Some(group)
+                && !entity.actor.is_dead()
+                && entity.location.is_in_area_id(area_id)
+        })
+        .collect()
+}
+
+fn hostiles(parent: &Rc<RefCell<EntityState>>) -> Vec<Rc<RefCell<EntityState>>> {
+    let mgr = GameState::turn_manager();
+    let mgr = mgr.borrow();
+    let parent = parent.borrow();
+    mgr.entity_iter()
+        .filter(|entity| {
+            let entity = entity.borrow();
+            !entity.actor.is_dead()
+                && !entity.actor.stats.hidden
+                && entity.location.is_in_area_id(&parent.location.area_id)
+        })
+        .filter(|entity| parent.is_hostile(entity))
+        .collect()
+}
+
+fn hostiles_have_area_effects(parent: &Rc<RefCell<EntityState>>) -> bool {
+    hostiles(parent).into_iter().any(|hostile| {
+        let hostile = hostile.borrow();
+        hostile.actor.ability_states.values().any(|state| {
+            let active = match &state.ability.active {
+                None => return false,
+                Some(active) => active,
+            };
+
+            active.ai.group == AIGroup::Multiple
+                && (active.ai.kind == AIKind::Damage || active.ai.kind == AIKind::Debuff)
+        })
+    })
+}
+
+/// Keeps the current focus if it is still a valid target, otherwise
+/// chooses the visible hostile that is most hurt and closest to the group
+fn choose_focus(
+    parent: &Rc<RefCell<EntityState>>,
+    members: &[Rc<RefCell<EntityState>>],
+    current: Option<usize>,
+) -> Option<usize> {
+    let area = GameState::get_area_state(&parent.borrow().location.area_id)?;
+    let area = area.borrow();
+    let vis_dist = area.area.area.vis_dist as f32;
+
+    let candidates: Vec<(usize, f32)> = hostiles(parent)
+        .into_iter()
+        .filter_map(|hostile| {
+            let hostile_ref = hostile.borrow();
+            let dist = members
+                .iter()
+                .filter(|member| area.has_visibility(&member.borrow(), &hostile_ref))
+                .map(|member| member.borrow().dist_to_entity(&hostile))
+                .min_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))?;
+
+            let max_hp = hostile_ref.actor.stats.max_hp.max(1);
+            let hp_frac = hostile_ref.actor.hp() as f32 / max_hp as f32;
+            Some((hostile_ref.index(), hp_frac + dist / vis_dist))
+        })
+        .collect();
+
+    if let Some(current) = current {
+        if candidates.iter().any(|(index, _)| *index == current) {
+            return Some(current);
+        }
+    }
+
+    candidates
+        .into_iter()
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+        .map(|(index, _)| index)
+}
+
+fn call_reinforcements(group: usize, enc_ref: &EncounterRef) {
+    let area = match GameState::get_area_state(&enc_ref.area_id) {
+        None => return,
+        Some(area) => area,
+    };
+
+    let indices = area
+        .borrow_mut()
+        .spawn_encounter_actors(enc_ref.encounter_index, group);
+    info!("AI group {} called {} reinforcements", group, indices.len());
+
+    let mgr = GameState::turn_manager();
+    for index in indices {
+        let entity = mgr.borrow().entity(index);
+        entity.borrow_mut().set_ai_active(true);
+    }
+}
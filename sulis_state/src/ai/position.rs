@@ -0,0 +1,362 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Tactical position evaluation.  Each position an entity could move to
+//! this turn is scored by flanking opportunity against a target, exposure
+//! to hostile attacks, hostile surfaces, ranged line of sight, how
+//! enclosed the position is, and how close it is to the rest of the
+//! entity's group.  The weights are adjusted for the entity's role.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use rlua::{self, Context, ToLua, Value};
+
+use crate::ai::GroupState;
+use crate::path_finder::{self, StateLocationChecker};
+use crate::{ActorState, AreaState, EntityState, GameState};
+use sulis_core::util::Point;
+use sulis_module::area::LocationChecker;
+use sulis_module::PositionWeights;
+
+/// The maximum number of squares considered when searching for positions
+const MAX_MOVES: u32 = 12;
+
+/// Group members closer than this to a position count as crowding it
+const SPREAD_DIST: f32 = 3.0;
+
+/// A single scored position.  `x` and `y` are the position of the entity's
+/// top left corner, as with its location.  Passed to Lua as a table with
+/// the same fields.
+#[derive(Clone, Debug)]
+pub struct PositionScore {
+    pub x: i32,
+    pub y: i32,
+    pub score: f32,
+
+    /// The number of squares moved to reach this position
+    pub moves: u32,
+
+    /// Whether melee attacks against the target would flank it from here
+    pub flanking: bool,
+
+    /// The number of hostiles able to attack this position, with ranged
+    /// attackers counting half
+    pub threat: f32,
+
+    /// Whether this position is covered by a surface created by a hostile
+    pub surface_danger: bool,
+
+    /// Whether a ranged attacker has line of sight to a hostile in range
+    pub line_of_sight: bool,
+
+    /// The number of the 8 adjacent positions that are passable
+    pub openness: u32,
+
+    /// The number of other group members crowding this position, while
+    /// the group is spreading out against area of effect abilities
+    pub crowding: u32,
+}
+
+impl<'lua> ToLua<'lua> for PositionScore {
+    fn to_lua(self, lua: Context<'lua>) -> rlua::Result<Value<'lua>> {
+        let table = lua.create_table()?;
+        table.set("x", self.x)?;
+        table.set("y", self.y)?;
+        table.set("score", self.score)?;
+        table.set("moves", self.moves)?;
+        table.set("flanking", self.flanking)?;
+        table.set("threat", self.threat)?;
+        table.set("surface_danger", self.surface_danger)?;
+        table.set("line_of_sight", self.line_of_sight)?;
+        table.set("openness", self.openness)?;
+        table.set("crowding", self.crowding)?;
+
+        Ok(Value::Table(table))
+    }
+}
+
+/// Scores every position the parent can reach this turn, best first.  If
+/// a `target` is specified, flanking and line of sight are only considered
+/// against that target.
+pub fn evaluate_positions(
+    parent: &Rc<RefCell<EntityState>>,
+    weights: &PositionWeights,
+    target: Option<&Rc<RefCell<EntityState>>>,
+) -> Vec<PositionScore> {
+    let area = match GameState::get_area_state(&parent.borrow().location.area_id) {
+        None => return Vec::new(),
+        Some(area) => area,
+    };
+    let area = area.borrow();
+
+    let points = path_finder::reachable_points(&area, &parent.borrow(), max_moves(parent));
+    let evaluator = Evaluator::new(parent, weights, &area);
+
+    let mut positions: Vec<PositionScore> = points
+        .into_iter()
+        .map(|(p, moves)| evaluator.score(p.x, p.y, moves, target))
+        .collect();
+
+    positions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    positions
+}
+
+/// Scores the single position at `x`, `y` for the parent, without any
+/// penalty for the movement needed to reach it.  Returns `None` if the
+/// parent would not fit inside the area at that position.
+pub fn score_position(
+    parent: &Rc<RefCell<EntityState>>,
+    weights: &PositionWeights,
+    x: i32,
+    y: i32,
+    target: Option<&Rc<RefCell<EntityState>>>,
+) -> Option<PositionScore> {
+    let area = GameState::get_area_state(&parent.borrow().location.area_id)?;
+    let area = area.borrow();
+    let size = Rc::clone(&parent.borrow().size);
+    if !size
+        .points(x, y)
+        .all(|p| area.area.area.coords_valid(p.x, p.y))
+    {
+        return None;
+    }
+
+    let evaluator = Evaluator::new(parent, weights, &area);
+    Some(evaluator.score(x, y, 0, target))
+}
+
+fn max_moves(parent: &Rc<RefCell<EntityState>>) -> u32 {
+    let parent = parent.borrow();
+    if parent.actor.stats.move_disabled {
+        return 0;
+    }
+
+    let mgr = GameState::turn_manager();
+    if !mgr.borrow().is_combat_active() {
+        return MAX_MOVES;
+    }
+
+    match parent.actor.get_move_ap_cost(1) {
+        0 => MAX_MOVES,
+        cost => (parent.actor.ap() / cost).min(MAX_MOVES),
+    }
+}
+
+struct Evaluator<'a> {
+    parent: &'a Rc<RefCell<EntityState>>,
+    weights: PositionWeights,
+    area: &'a AreaState,
+    hostiles: Vec<Rc<RefCell<EntityState>>>,
+    allies_to_avoid: Vec<Rc<RefCell<EntityState>>>,
+    ranged: bool,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(
+        parent: &'a Rc<RefCell<EntityState>>,
+        weights: &PositionWeights,
+        area: &'a AreaState,
+    ) -> Evaluator<'a> {
+        let area_id = &parent.borrow().location.area_id;
+        let mgr = GameState::turn_manager();
+        let hostiles = mgr
+            .borrow()
+            .entity_iter()
+            .filter(|entity| {
+                let entity = entity.borrow();
+                !entity.actor.is_dead()
+                    && !entity.actor.stats.hidden
+                    && entity.location.is_in_area_id(area_id)
+            })
+            .filter(|entity| parent.borrow().is_hostile(entity))
+            .collect();
+
+        let ranged = parent.borrow().actor.stats.attack_is_ranged();
+        let role = GroupState::role(&parent.borrow());
+        let weights = GroupState::role_position_weights(role, weights);
+
+        let allies_to_avoid = match GroupState::for_entity(parent) {
+            Some(group) if group.spread => group.members,
+            _ => Vec::new(),
+        };
+
+        Evaluator {
+            parent,
+            weights,
+            area,
+            hostiles,
+            allies_to_avoid,
+            ranged,
+        }
+    }
+
+    fn score(
+        &self,
+        x: i32,
+        y: i32,
+        moves: u32,
+        target: Option<&Rc<RefCell<EntityState>>>,
+    ) -> PositionScore {
+        let w = &self.weights;
+        let flanking = match target {
+            Some(target) if !self.ranged => self.is_flanking(x, y, target),
+            _ => false,
+        };
+        let threat = self.threat(x, y);
+        let surface_danger = self.surface_danger(x, y);
+        let line_of_sight = self.ranged && self.line_of_sight(x, y, target);
+        let openness = self.openness(x, y);
+        let crowding = self.crowding(x, y);
+
+        let mut score = -w.threat * threat - w.movement * moves as f32 - w.spread * crowding as f32;
+        if flanking {
+            score += w.flanking;
+        }
+        if surface_danger {
+            score -= w.surface;
+        }
+        if line_of_sight {
+            score += w.line_of_sight;
+        }
+        if !self.ranged && !self.hostiles.is_empty() {
+            score += w.chokepoint * (8 - openness) as f32 / 8.0;
+        }
+
+        PositionScore {
+            x,
+            y,
+            score,
+            moves,
+            flanking,
+            threat,
+            surface_danger,
+            line_of_sight,
+            openness,
+            crowding,
+        }
+    }
+
+    fn crowding(&self, x: i32, y: i32) -> u32 {
+        let size = &self.parent.borrow().size;
+        let p = Point::new(x, y);
+        self.allies_to_avoid
+            .iter()
+            .filter(|ally| ally.borrow().dist(p, size) < SPREAD_DIST)
+            .count() as u32
+    }
+
+    fn is_flanking(&self, x: i32, y: i32, target: &Rc<RefCell<EntityState>>) -> bool {
+        let center = {
+            let parent = self.parent.borrow();
+            let dist = target.borrow().dist(Point::new(x, y), &parent.size);
+            if parent.actor.stats.attack_disabled || !parent.actor.can_reach(dist) {
+                return false;
+            }
+            (
+                x as f32 + parent.size.width as f32 / 2.0,
+                y as f32 + parent.size.height as f32 / 2.0,
+            )
+        };
+
+        ActorState::is_flanking_from(self.parent, center, target)
+    }
+
+    fn threat(&self, x: i32, y: i32) -> f32 {
+        let parent = self.parent.borrow();
+        let vis_dist = self.area.area.area.vis_dist as f32;
+        let p = Point::new(x, y);
+
+        let mut threat = 0.0;
+        for hostile in self.hostiles.iter() {
+            let hostile = hostile.borrow();
+            if hostile.actor.stats.attack_disabled {
+                continue;
+            }
+
+            let dist = hostile.dist(p, &parent.size);
+            if hostile.actor.stats.attack_is_ranged() {
+                if dist < vis_dist && self.area.is_visible_at(&hostile, &parent.size, x, y) {
+                    threat += 0.5;
+                }
+            } else if hostile.actor.can_reach(dist) {
+                threat += 1.0;
+            }
+        }
+        threat
+    }
+
+    fn surface_danger(&self, x: i32, y: i32) -> bool {
+        let surfaces = self.area.surfaces_at(&self.parent.borrow().size, x, y);
+        if surfaces.is_empty() {
+            return false;
+        }
+
+        let mgr = GameState::turn_manager();
+        let mgr = mgr.borrow();
+        surfaces.into_iter().any(|index| {
+            let effect = match mgr.effect_checked(index) {
+                None => return false,
+                Some(effect) => effect,
+            };
+
+            let aura = effect.surface.as_ref().and_then(|surface| surface.aura);
+            aura.into_iter()
+                .chain(effect.callbacks.iter().map(|cb| cb.parent()))
+                .filter_map(|index| mgr.entity_checked(index))
+                .any(|owner| self.parent.borrow().is_hostile(&owner))
+        })
+    }
+
+    fn line_of_sight(&self, x: i32, y: i32, target: Option<&Rc<RefCell<EntityState>>>) -> bool {
+        let parent = self.parent.borrow();
+        let p = Point::new(x, y);
+        let vis_dist = self.area.area.area.vis_dist as f32;
+
+        let in_sight = |entity: &Rc<RefCell<EntityState>>| {
+            let entity = entity.borrow();
+            let dist = entity.dist(p, &parent.size);
+            dist < vis_dist
+                && parent.actor.can_reach(dist)
+                && self.area.has_visibility_at(&parent.size, x, y, &entity)
+        };
+
+        match target {
+            Some(target) => in_sight(target),
+            None => self.hostiles.iter().any(in_sight),
+        }
+    }
+
+    fn openness(&self, x: i32, y: i32) -> u32 {
+        let parent = self.parent.borrow();
+        let checker = StateLocationChecker::new(self.area, &parent, Vec::new());
+
+        let mut count = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let (nx, ny) = (x + dx, y + dy);
+                if self.area.area.area.coords_valid(nx, ny) && checker.passable(nx, ny) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
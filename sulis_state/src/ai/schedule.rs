@@ -0,0 +1,269 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Out of combat schedules.  Outside of combat, each entity in the current
+//! area with a schedule walks the waypoints of the activity for the current
+//! hour, wanders around while waiting, and plays its idle animations.
+//! Each schedule draws from its own saved random source, so wandering never
+//! advances the game's shared one at frame dependent times.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::animation::{particle_generator::Param, Anim};
+use crate::{EntityState, GameState};
+use sulis_core::util::{ExtInt, Point, ReproducibleRandom};
+use sulis_module::schedule::IdleAnimation;
+use sulis_module::Schedule;
+
+/// The time in milliseconds before trying again when a waypoint can't be
+/// reached
+const RETRY_MILLIS: u32 = 2000;
+
+/// Entities this close to a waypoint they can't reach have arrived
+const ARRIVE_DIST: f32 = 1.0;
+
+/// The progress of an entity through its schedule
+#[derive(Debug)]
+pub struct ScheduleState {
+    schedule: Rc<Schedule>,
+    home: Point,
+    activity: Option<usize>,
+    waypoint: usize,
+    arrived: bool,
+    wait_millis: u32,
+    idle_millis: u32,
+    random: ReproducibleRandom,
+}
+
+impl ScheduleState {
+    /// Creates the state for an entity starting the `schedule` at `home`,
+    /// which is where it stays during activities without waypoints.  If no
+    /// `random` source is given, one is seeded from the game's
+    pub fn new(
+        schedule: Rc<Schedule>,
+        home: Point,
+        random: Option<ReproducibleRandom>,
+    ) -> ScheduleState {
+        let mut random = random.unwrap_or_else(|| {
+            let seed = GameState::with_random(|rand| rand.gen(0, u64::MAX));
+            ReproducibleRandom::new(Some(seed as u128))
+        });
+        let idle_millis = next_idle_millis(&schedule, &mut random);
+        ScheduleState {
+            schedule,
+            home,
+            activity: None,
+            waypoint: 0,
+            arrived: false,
+            wait_millis: 0,
+            idle_millis,
+            random,
+        }
+    }
+
+    pub fn schedule(&self) -> &Rc<Schedule> {
+        &self.schedule
+    }
+
+    /// The point the entity returns to during activities without waypoints
+    pub fn home(&self) -> Point {
+        self.home
+    }
+
+    pub fn random(&self) -> &ReproducibleRandom {
+        &self.random
+    }
+
+    fn update(&mut self, entity: &Rc<RefCell<EntityState>>, hour: u32, millis: u32) {
+        let index = self.schedule.activity_index(hour);
+        if index != self.activity {
+            debug!(
+                "'{}' starting schedule activity {:?}",
+                entity.borrow().unique_id(),
+                index
+            );
+            self.activity = index;
+            self.waypoint = 0;
+            self.arrived = false;
+            self.wait_millis = 0;
+        }
+
+        if GameState::has_blocking_animations(entity) {
+            return;
+        }
+
+        let schedule = Rc::clone(&self.schedule);
+        let activity = index.map(|index| &schedule.activities[index]);
+        let waypoints = activity.map_or(&[][..], |activity| &activity.waypoints[..]);
+        let anchor = match waypoints.get(self.waypoint) {
+            None => self.home,
+            Some(waypoint) => waypoint.location,
+        };
+
+        if !self.arrived {
+            if self.wait_millis > millis {
+                self.wait_millis -= millis;
+                return;
+            }
+
+            if !self.walk_to(entity, anchor) {
+                return;
+            }
+
+            self.arrived = true;
+            self.wait_millis = waypoints
+                .get(self.waypoint)
+                .map_or(0, |waypoint| waypoint.wait_millis);
+        }
+
+        if waypoints.len() > 1 {
+            if self.wait_millis > millis {
+                self.wait_millis -= millis;
+            } else {
+                self.waypoint = (self.waypoint + 1) % waypoints.len();
+                self.arrived = false;
+                self.wait_millis = 0;
+                return;
+            }
+        }
+
+        if self.idle_millis > millis {
+            self.idle_millis -= millis;
+            return;
+        }
+        self.idle_millis = next_idle_millis(&self.schedule, &mut self.random);
+
+        let wander_radius = activity.map_or(0, |activity| activity.wander_radius) as i32;
+        let idle = &schedule.idle;
+        let random = &mut self.random;
+        if wander_radius > 0 && (idle.is_empty() || random.gen(0, 2) == 0) {
+            let x = anchor.x + random.gen(-wander_radius, wander_radius + 1);
+            let y = anchor.y + random.gen(-wander_radius, wander_radius + 1);
+            move_towards(entity, Point::new(x, y));
+        } else if !idle.is_empty() {
+            let anim = idle[random.gen(0, idle.len())];
+            GameState::add_animation(idle_anim(entity, anim, random));
+        }
+    }
+
+    /// Starts the entity walking to `p`.  Returns true if it is already
+    /// there, or as close as it can get
+    fn walk_to(&mut self, entity: &Rc<RefCell<EntityState>>, p: Point) -> bool {
+        if entity.borrow().location.to_point() == p {
+            return true;
+        }
+
+        if move_towards(entity, p) {
+            return false;
+        }
+
+        if entity.borrow().dist_to_point(p) < ARRIVE_DIST {
+            return true;
+        }
+
+        self.wait_millis = RETRY_MILLIS;
+        false
+    }
+}
+
+/// Updates the schedules of entities in the current area.  Schedules are
+/// paused during combat and while a modal window, such as a conversation,
+/// is open.
+pub fn update_schedules(millis: u32) {
+    if GameState::is_modal_locked() {
+        return;
+    }
+
+    let mgr = GameState::turn_manager();
+    if mgr.borrow().is_combat_active() {
+        return;
+    }
+
+    let area_id = GameState::area_state().borrow().area.area.id.to_string();
+    let entities: Vec<_> = mgr
+        .borrow()
+        .entity_iter()
+        .filter(|entity| {
+            let entity = entity.borrow();
+            entity.schedule.is_some()
+                && entity.location.is_in_area_id(&area_id)
+                && !entity.is_party_member()
+                && !entity.is_ai_active()
+                && !entity.actor.is_dead()
+        })
+        .collect();
+
+    let hour = mgr.borrow().current_time().hour;
+    for entity in entities {
+        let state = entity.borrow_mut().schedule.take();
+        if let Some(mut state) = state {
+            state.update(&entity, hour, millis);
+            entity.borrow_mut().schedule = Some(state);
+        }
+    }
+}
+
+/// Starts the entity moving so its top left corner is at `p`, returning
+/// false if no path could be found
+fn move_towards(entity: &Rc<RefCell<EntityState>>, p: Point) -> bool {
+    let (x, y) = {
+        let entity = entity.borrow();
+        (
+            (p.x + entity.size.width / 2) as f32,
+            (p.y + entity.size.height / 2) as f32,
+        )
+    };
+
+    GameState::move_out_of_combat(entity, x, y, 0.5)
+}
+
+fn next_idle_millis(schedule: &Schedule, random: &mut ReproducibleRandom) -> u32 {
+    let interval = schedule.idle_interval.max(2);
+    random.gen(interval / 2, interval * 3 / 2)
+}
+
+/// Creates a parabolic animation of `height`, starting and ending at `base`
+fn idle_param(base: f32, height: f32, secs: f32) -> Param {
+    Param::with_accel(base, 4.0 * height / secs, -4.0 * height / (secs * secs))
+}
+
+fn idle_anim(
+    entity: &Rc<RefCell<EntityState>>,
+    anim: IdleAnimation,
+    random: &mut ReproducibleRandom,
+) -> Anim {
+    match anim {
+        IdleAnimation::Hop => Anim::new_entity_subpos(
+            entity,
+            ExtInt::Int(500),
+            Param::fixed(0.0),
+            idle_param(0.0, -0.3, 0.5),
+        ),
+        IdleAnimation::Shuffle => {
+            let dir = if random.gen(0, 2) == 0 { -1.0 } else { 1.0 };
+            Anim::new_entity_subpos(
+                entity,
+                ExtInt::Int(800),
+                idle_param(0.0, 0.25 * dir, 0.8),
+                Param::fixed(0.0),
+            )
+        }
+        IdleAnimation::Breathe => {
+            Anim::new_entity_scale(entity, ExtInt::Int(1600), idle_param(1.0, 0.05, 1.6))
+        }
+    }
+}
@@ -0,0 +1,762 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! The native utility AI.  Every candidate action for an entity - attacking
+//! a target, using an ability or item on a target, moving to flank, moving
+//! to a better tactical position, or retreating - is scored with the
+//! weighted considerations of the entity's `UtilityWeights`, and the best
+//! scoring action is taken.  Entities in an encounter group also consider
+//! the group's focus target, their role, and whether the group is
+//! retreating.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use rlua::{self, Context, ToLua, Value};
+
+use crate::ai::position::{self, PositionScore};
+use crate::ai::{GroupState, State};
+use crate::script::{ScriptEntity, ScriptItemKind};
+use crate::{ActorState, EntityState, GameState, Script};
+use sulis_module::ability::{AIData, AIKind, AIRange};
+use sulis_module::{AIRole, Module, QuickSlot, UtilityWeights};
+
+/// The distance an entity attempts to move when retreating
+const RETREAT_DIST: f32 = 12.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UtilityActionKind {
+    Attack,
+    UseAbility(String),
+    UseItem(QuickSlot),
+    MoveToFlank { x: i32, y: i32 },
+    Reposition { x: i32, y: i32 },
+    Retreat,
+}
+
+impl UtilityActionKind {
+    fn name(&self) -> &'static str {
+        use UtilityActionKind::*;
+        match self {
+            Attack => "Attack",
+            UseAbility(_) => "Ability",
+            UseItem(_) => "Item",
+            MoveToFlank { .. } => "Flank",
+            Reposition { .. } => "Reposition",
+            Retreat => "Retreat",
+        }
+    }
+}
+
+/// A scored candidate action.  Passed to Lua override functions as a table
+/// with the `kind`, `score`, and `target` of the action, as well as the
+/// `ability` ID, item `slot`, or destination position `x` and `y` as
+/// relevant.
+#[derive(Clone)]
+pub struct UtilityAction {
+    pub kind: UtilityActionKind,
+    pub target: Option<Rc<RefCell<EntityState>>>,
+    pub score: f32,
+}
+
+impl UtilityAction {
+    fn target_index(&self) -> Option<usize> {
+        self.target.as_ref().map(|t| t.borrow().index())
+    }
+}
+
+impl<'lua> ToLua<'lua> for UtilityAction {
+    fn to_lua(self, lua: Context<'lua>) -> rlua::Result<Value<'lua>> {
+        let table = lua.create_table()?;
+        table.set("kind", self.kind.name())?;
+        table.set("score", self.score)?;
+        if let Some(target) = &self.target {
+            table.set("target", ScriptEntity::from(target))?;
+        }
+
+        match self.kind {
+            UtilityActionKind::UseAbility(id) => table.set("ability", id)?,
+            UtilityActionKind::UseItem(slot) => table.set("slot", format!("{:?}", slot))?,
+            UtilityActionKind::MoveToFlank { x, y } | UtilityActionKind::Reposition { x, y } => {
+                table.set("x", x)?;
+                table.set("y", y)?;
+            }
+            UtilityActionKind::Attack | UtilityActionKind::Retreat => (),
+        }
+
+        Ok(Value::Table(table))
+    }
+}
+
+/// The utility AI state for one entity over the course of its turn
+pub struct UtilityAI {
+    /// Actions that could not be carried out this turn, identified by kind
+    /// and target index, which are not considered again
+    failed: Vec<(UtilityActionKind, Option<usize>)>,
+}
+
+impl UtilityAI {
+    pub fn new() -> UtilityAI {
+        UtilityAI { failed: Vec::new() }
+    }
+
+    /// Chooses and starts the next action for the parent, returning the
+    /// resulting AI state
+    pub fn run(
+        &mut self,
+        parent: &Rc<RefCell<EntityState>>,
+        script: &str,
+        script_override: Option<&str>,
+        weights: &UtilityWeights,
+    ) -> State {
+        let actions: Vec<UtilityAction> = evaluate(parent, weights)
+            .into_iter()
+            .filter(|action| action.score > 0.0)
+            .filter(|action| {
+                let key = (action.kind.clone(), action.target_index());
+                !self.failed.contains(&key)
+            })
+            .collect();
+
+        if let Some(func) = script_override {
+            if let Some(state) = Script::ai_override(parent, script, func, actions.clone()) {
+                return state;
+            }
+        }
+
+        let action = match actions.into_iter().next() {
+            None => return State::End,
+            Some(action) => action,
+        };
+
+        debug!(
+            "Utility AI for '{}' chose {:?} with score {}",
+            parent.borrow().actor.actor.name,
+            action.kind,
+            action.score
+        );
+
+        match perform(parent, &action) {
+            Some(state) => state,
+            None => {
+                debug!("  Unable to perform action.  Choosing another.");
+                let target = action.target_index();
+                self.failed.push((action.kind, target));
+                State::Run
+            }
+        }
+    }
+}
+
+struct Candidates<'a> {
+    parent: &'a Rc<RefCell<EntityState>>,
+    weights: &'a UtilityWeights,
+    hostiles: Vec<Rc<RefCell<EntityState>>>,
+    friendlies: Vec<Rc<RefCell<EntityState>>>,
+    vis_dist: f32,
+    role: AIRole,
+    group: Option<GroupState>,
+    actions: Vec<UtilityAction>,
+}
+
+/// Scores all candidate actions currently available to the parent, best
+/// first.  Actions may have a score of zero or less, in which case they
+/// should not be taken.
+pub fn evaluate(parent: &Rc<RefCell<EntityState>>, weights: &UtilityWeights) -> Vec<UtilityAction> {
+    let area_id = parent.borrow().location.area_id.to_string();
+    let vis_dist = match GameState::get_area_state(&area_id) {
+        None => return Vec::new(),
+        Some(area) => area.borrow().area.area.vis_dist as f32,
+    };
+
+    let mut hostiles = Vec::new();
+    let mut friendlies = Vec::new();
+    let mgr = GameState::turn_manager();
+    for entity in mgr.borrow().entity_iter() {
+        {
+            let entity = entity.borrow();
+            if entity.actor.is_dead() || !entity.location.is_in_area_id(&area_id) {
+                continue;
+            }
+        }
+
+        if parent.borrow().is_hostile(&entity) {
+            if !entity.borrow().actor.stats.hidden {
+                hostiles.push(entity);
+            }
+        } else {
+            friendlies.push(entity);
+        }
+    }
+
+    let role = GroupState::role(&parent.borrow());
+    let group = GroupState::for_entity(parent);
+
+    let mut candidates = Candidates {
+        parent,
+        weights,
+        hostiles,
+        friendlies,
+        vis_dist,
+        role,
+        group,
+        actions: Vec::new(),
+    };
+
+    candidates.add_attacks();
+    candidates.add_abilities();
+    candidates.add_items();
+    candidates.add_flanks();
+    candidates.add_reposition();
+    candidates.add_retreat();
+
+    let mut actions = candidates.actions;
+    actions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    actions
+}
+
+fn hp_frac(entity: &Rc<RefCell<EntityState>>) -> f32 {
+    let entity = entity.borrow();
+    let max_hp = entity.actor.stats.max_hp;
+    if max_hp <= 0 {
+        return 1.0;
+    }
+    (entity.actor.hp() as f32 / max_hp as f32).clamp(0.0, 1.0)
+}
+
+fn effect_tags_score(entity: &Rc<RefCell<EntityState>>, tags: &HashMap<String, f32>) -> f32 {
+    if tags.is_empty() {
+        return 0.0;
+    }
+
+    let mgr = GameState::turn_manager();
+    let mgr = mgr.borrow();
+    let entity = entity.borrow();
+    let active: HashSet<&str> = entity
+        .actor
+        .effects_iter()
+        .map(|index| mgr.effect(*index).tag.as_str())
+        .collect();
+
+    active.into_iter().filter_map(|tag| tags.get(tag)).sum()
+}
+
+fn priority_factor(priority: u32) -> f32 {
+    (100 - priority.min(100)) as f32 / 100.0
+}
+
+impl<'a> Candidates<'a> {
+    fn push(
+        &mut self,
+        kind: UtilityActionKind,
+        target: Option<&Rc<RefCell<EntityState>>>,
+        score: f32,
+    ) {
+        self.actions.push(UtilityAction {
+            kind,
+            target: target.map(Rc::clone),
+            score,
+        });
+    }
+
+    /// The considerations common to any target
+    fn target_score(&self, target: &Rc<RefCell<EntityState>>) -> f32 {
+        let w = self.weights;
+        let dist = self.parent.borrow().dist_to_entity(target);
+
+        -w.distance * dist / self.vis_dist
+            + w.target_hp * (1.0 - hp_frac(target))
+            + effect_tags_score(target, &w.target_effect_tags)
+    }
+
+    /// The considerations for a hostile target, including its threat and
+    /// whether it is the group's focus
+    fn hostile_score(&self, target: &Rc<RefCell<EntityState>>) -> f32 {
+        let mut score = self.target_score(target);
+        if target.borrow().can_reach(self.parent) {
+            score += self.weights.threat;
+        }
+
+        let group = match &self.group {
+            None => return score,
+            Some(group) => group,
+        };
+
+        if let Some(focus) = &group.focus {
+            if Rc::ptr_eq(focus, target) {
+                score += self.weights.focus;
+            }
+        }
+
+        if self.role == AIRole::Tank {
+            let threatens_ally = group.members.iter().any(|member| {
+                GroupState::role(&member.borrow()) != AIRole::Tank
+                    && target.borrow().can_reach(member)
+            });
+            if threatens_ally {
+                score += self.weights.protect;
+            }
+        }
+
+        score
+    }
+
+    /// The bonus for healers using abilities and items of the specified kind
+    fn support_score(&self, kind: AIKind) -> f32 {
+        match (self.role, kind) {
+            (AIRole::Healer, AIKind::Heal) | (AIRole::Healer, AIKind::Buff) => self.weights.support,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the valid targets and their scores for an ability or item
+    /// with the specified AI data
+    fn targets_for(&self, ai: &AIData) -> Vec<(Rc<RefCell<EntityState>>, f32)> {
+        let threshold = self.weights.heal_threshold;
+
+        if ai.range == AIRange::Personal {
+            let score = match ai.kind {
+                AIKind::Heal => {
+                    let frac = hp_frac(self.parent);
+                    if frac >= threshold {
+                        return Vec::new();
+                    }
+                    self.weights.target_hp * (1.0 - frac)
+                }
+                _ => 0.0,
+            };
+            return vec![(Rc::clone(self.parent), score)];
+        }
+
+        match ai.kind {
+            AIKind::Special => Vec::new(),
+            AIKind::Damage | AIKind::Debuff => self
+                .hostiles
+                .iter()
+                .map(|t| (Rc::clone(t), self.hostile_score(t)))
+                .collect(),
+            AIKind::Heal => self
+                .friendlies
+                .iter()
+                .filter(|t| hp_frac(t) < threshold)
+                .map(|t| (Rc::clone(t), self.target_score(t)))
+                .collect(),
+            AIKind::Buff => self
+                .friendlies
+                .iter()
+                .map(|t| (Rc::clone(t), self.target_score(t)))
+                .collect(),
+        }
+    }
+
+    fn add_attacks(&mut self) {
+        {
+            let parent = self.parent.borrow();
+            if parent.actor.stats.attack_disabled || !parent.actor.has_ap_to_attack() {
+                return;
+            }
+        }
+
+        for target in self.hostiles.clone() {
+            let mut score = self.weights.attack + self.hostile_score(&target);
+            let can_reach = self.parent.borrow().can_reach(&target);
+            if can_reach && ActorState::is_flanking(self.parent, &target) {
+                score += self.weights.flanking;
+            }
+            self.push(UtilityActionKind::Attack, Some(&target), score);
+        }
+    }
+
+    fn add_abilities(&mut self) {
+        let abilities: Vec<(String, AIData)> = {
+            let parent = self.parent.borrow();
+            parent
+                .actor
+                .ability_states
+                .iter()
+                .filter(|(id, state)| !state.is_active_mode() && parent.actor.can_activate(id))
+                .filter_map(|(id, state)| {
+                    let active = state.ability.active.as_ref()?;
+                    Some((id.to_string(), active.ai))
+                })
+                .collect()
+        };
+
+        for (id, ai) in abilities {
+            if ai.kind == AIKind::Special {
+                continue;
+            }
+
+            let base = self.weights.ability
+                + self.weights.priority * priority_factor(ai.priority)
+                + self.support_score(ai.kind);
+            for (target, score) in self.targets_for(&ai) {
+                self.push(
+                    UtilityActionKind::UseAbility(id.to_string()),
+                    Some(&target),
+                    base + score,
+                );
+            }
+        }
+    }
+
+    fn add_items(&mut self) {
+        let items: Vec<(QuickSlot, AIData)> = {
+            let parent = self.parent.borrow();
+            QuickSlot::usable_iter()
+                .filter(|slot| parent.actor.can_use_quick(**slot))
+                .filter_map(|slot| {
+                    let item = parent.actor.inventory().quick(*slot)?;
+                    let usable = item.item.usable.as_ref()?;
+                    Some((*slot, usable.ai))
+                })
+                .collect()
+        };
+
+        for (slot, ai) in items {
+            if ai.kind == AIKind::Special {
+                continue;
+            }
+
+            let base = self.weights.item
+                + self.weights.priority * priority_factor(ai.priority)
+                + self.support_score(ai.kind);
+            for (target, score) in self.targets_for(&ai) {
+                self.push(
+                    UtilityActionKind::UseItem(slot),
+                    Some(&target),
+                    base + score,
+                );
+            }
+        }
+    }
+
+    fn add_flanks(&mut self) {
+        {
+            let parent = self.parent.borrow();
+            if parent.actor.stats.attack_disabled
+                || parent.actor.stats.attack_is_ranged()
+                || !parent.actor.has_ap_to_attack()
+                || !parent.can_move()
+            {
+                return;
+            }
+        }
+
+        for target in self.hostiles.clone() {
+            if target.borrow().actor.stats.flanked_immunity {
+                continue;
+            }
+
+            let can_reach = self.parent.borrow().can_reach(&target);
+            if can_reach && ActorState::is_flanking(self.parent, &target) {
+                continue;
+            }
+
+            let (x, y) = match self.flank_position(&target) {
+                None => continue,
+                Some(position) => (position.x, position.y),
+            };
+
+            let score =
+                self.weights.move_to_flank + self.weights.flanking + self.hostile_score(&target);
+            self.push(
+                UtilityActionKind::MoveToFlank { x, y },
+                Some(&target),
+                score,
+            );
+        }
+    }
+
+    /// Finds the best reachable position from which the parent would flank
+    /// the target and still have the AP to attack, if there is one
+    fn flank_position(&self, target: &Rc<RefCell<EntityState>>) -> Option<PositionScore> {
+        let ap_to_move = {
+            let actor = &self.parent.borrow().actor;
+            actor
+                .ap()
+                .saturating_sub(actor.stats.attack_cost.max(0) as u32)
+        };
+
+        position::evaluate_positions(self.parent, &self.weights.position, Some(target))
+            .into_iter()
+            .filter(|position| position.flanking && position.moves > 0)
+            .find(|position| {
+                self.parent.borrow().actor.get_move_ap_cost(position.moves) <= ap_to_move
+            })
+    }
+
+    fn add_reposition(&mut self) {
+        if self.hostiles.is_empty() || !self.parent.borrow().can_move() {
+            return;
+        }
+
+        let positions = position::evaluate_positions(self.parent, &self.weights.position, None);
+        let current = match positions.iter().find(|position| position.moves == 0) {
+            None => return,
+            Some(position) => position.score,
+        };
+
+        let best = match positions.first() {
+            Some(position) if position.moves > 0 => position,
+            _ => return,
+        };
+
+        let score = self.weights.reposition * (best.score - current);
+        let (x, y) = (best.x, best.y);
+        self.push(UtilityActionKind::Reposition { x, y }, None, score);
+    }
+
+    fn add_retreat(&mut self) {
+        if self.hostiles.is_empty() || !self.parent.borrow().can_move() {
+            return;
+        }
+
+        let threatening = self
+            .hostiles
+            .iter()
+            .filter(|h| h.borrow().can_reach(self.parent))
+            .count();
+
+        let w = self.weights;
+        let mut score = w.retreat * (1.0 - hp_frac(self.parent)) * threatening as f32
+            + effect_tags_score(self.parent, &w.retreat_effect_tags);
+        if self.group.as_ref().is_some_and(|group| group.retreating) {
+            score += w.group_retreat;
+        }
+        self.push(UtilityActionKind::Retreat, None, score);
+    }
+}
+
+/// Starts the specified action.  Returns the resulting AI state, or `None`
+/// if the action could not be carried out.
+fn perform(parent: &Rc<RefCell<EntityState>>, action: &UtilityAction) -> Option<State> {
+    use UtilityActionKind::*;
+    match (&action.kind, &action.target) {
+        (Attack, Some(target)) => attack(parent, target),
+        (UseAbility(id), Some(target)) => {
+            let ability = Module::ability(id)?;
+            let ai = ability.active.as_ref()?.ai;
+            if !approach(parent, target, ai.range)? {
+                return Some(State::Wait(10));
+            }
+            Script::ability_on_activate(parent, &ability);
+            handle_targeter(target)
+        }
+        (UseItem(slot), Some(target)) => {
+            let ai = {
+                let parent = parent.borrow();
+                let item = parent.actor.inventory().quick(*slot)?;
+                item.item.usable.as_ref()?.ai
+            };
+            if !approach(parent, target, ai.range)? {
+                return Some(State::Wait(10));
+            }
+            Script::item_on_activate(parent, ScriptItemKind::Quick(*slot));
+            handle_targeter(target)
+        }
+        (MoveToFlank { x, y }, Some(_)) | (Reposition { x, y }, _) => {
+            move_to_position(parent, *x, *y)
+        }
+        (Retreat, _) => retreat(parent),
+        (_, None) => None,
+    }
+}
+
+fn attack(parent: &Rc<RefCell<EntityState>>, target: &Rc<RefCell<EntityState>>) -> Option<State> {
+    let ranged = parent.borrow().actor.stats.attack_is_ranged();
+    if !ranged {
+        if parent.borrow().can_reach(target) {
+            EntityState::attack(parent, target, None, true);
+            return Some(State::Wait(10));
+        }
+
+        return if GameState::move_towards(parent, target) {
+            Some(State::Wait(10))
+        } else {
+            None
+        };
+    }
+
+    let area = GameState::area_state();
+    let vis_dist = area.borrow().area.area.vis_dist as f32;
+    let dist = parent.borrow().dist_to_entity(target);
+    let visible = area
+        .borrow()
+        .has_visibility(&parent.borrow(), &target.borrow());
+
+    let move_dist = if dist > vis_dist - 2.0 {
+        vis_dist - 2.0
+    } else if !visible {
+        dist - 2.0
+    } else {
+        EntityState::attack(parent, target, None, true);
+        return Some(State::Wait(10));
+    };
+
+    if move_towards_entity(parent, target, move_dist) {
+        Some(State::Wait(10))
+    } else {
+        None
+    }
+}
+
+/// Moves the parent towards the target if it is not yet within the range
+/// for an ability or item.  Returns true if the parent is already in range,
+/// false if it has started moving, or `None` if it is unable to move.
+fn approach(
+    parent: &Rc<RefCell<EntityState>>,
+    target: &Rc<RefCell<EntityState>>,
+    range: AIRange,
+) -> Option<bool> {
+    let target_dist = match range {
+        AIRange::Personal => return Some(true),
+        AIRange::Reach => {
+            if parent.borrow().can_reach(target) {
+                return Some(true);
+            }
+            return if GameState::move_towards(parent, target) {
+                Some(false)
+            } else {
+                None
+            };
+        }
+        AIRange::Short => 8.0,
+        AIRange::Visible => {
+            let area = GameState::area_state();
+            let vis_dist = area.borrow().area.area.vis_dist as f32;
+            vis_dist - 1.0
+        }
+    };
+
+    if parent.borrow().dist_to_entity(target) < target_dist {
+        return Some(true);
+    }
+
+    if move_towards_entity(parent, target, target_dist) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn move_towards_entity(
+    parent: &Rc<RefCell<EntityState>>,
+    target: &Rc<RefCell<EntityState>>,
+    dist: f32,
+) -> bool {
+    let (x, y) = {
+        let target = target.borrow();
+        (
+            target.location.x as f32 + (target.size.width / 2) as f32,
+            target.location.y as f32 + (target.size.height / 2) as f32,
+        )
+    };
+    GameState::move_towards_point(parent, Vec::new(), x, y, dist, None)
+}
+
+/// Moves the parent so its location is exactly `x`, `y`
+fn move_to_position(parent: &Rc<RefCell<EntityState>>, x: i32, y: i32) -> Option<State> {
+    let (x, y) = {
+        let parent = parent.borrow();
+        (
+            (x + parent.size.width / 2) as f32,
+            (y + parent.size.height / 2) as f32,
+        )
+    };
+
+    if GameState::move_towards_point(parent, Vec::new(), x, y, 0.5, None) {
+        Some(State::Wait(10))
+    } else {
+        None
+    }
+}
+
+/// Points the targeter created by an ability or item, if any, at the target
+fn handle_targeter(target: &Rc<RefCell<EntityState>>) -> Option<State> {
+    let targeter = {
+        let area = GameState::area_state();
+        let mut area = area.borrow_mut();
+        area.targeter()
+    };
+
+    let targeter = match targeter {
+        None => return Some(State::Wait(10)),
+        Some(targeter) => targeter,
+    };
+
+    let (x, y) = {
+        let target = target.borrow();
+        (target.location.x, target.location.y)
+    };
+
+    let mut targeter = targeter.borrow_mut();
+    targeter.on_mouse_move(x, y);
+    if targeter.is_valid_to_activate() {
+        targeter.on_activate();
+        Some(State::Wait(10))
+    } else {
+        targeter.on_cancel();
+        None
+    }
+}
+
+/// Moves directly away from the average direction of all visible hostiles
+fn retreat(parent: &Rc<RefCell<EntityState>>) -> Option<State> {
+    let area = GameState::area_state();
+    let (px, py) = (parent.borrow().location.x, parent.borrow().location.y);
+
+    let mut total_x = 0.0;
+    let mut total_y = 0.0;
+    let mut count = 0;
+    let mgr = GameState::turn_manager();
+    for entity in mgr.borrow().entity_iter() {
+        if !parent.borrow().is_hostile(&entity) || entity.borrow().actor.is_dead() {
+            continue;
+        }
+
+        if !area
+            .borrow()
+            .has_visibility(&parent.borrow(), &entity.borrow())
+        {
+            continue;
+        }
+
+        let entity = entity.borrow();
+        let angle = ((py - entity.location.y) as f32).atan2((px - entity.location.x) as f32);
+        total_x += angle.cos();
+        total_y += angle.sin();
+        count += 1;
+    }
+
+    if count == 0 {
+        return None;
+    }
+
+    let angle = total_y.atan2(total_x);
+    let x = px as f32 + angle.cos() * RETREAT_DIST;
+    let y = py as f32 + angle.sin() * RETREAT_DIST;
+
+    for thresh in 1..=10 {
+        if GameState::move_towards_point(parent, Vec::new(), x, y, thresh as f32, None) {
+            return Some(State::End);
+        }
+    }
+
+    None
+}
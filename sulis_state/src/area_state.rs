@@ -25,10 +25,11 @@ use crate::save_state::AreaSaveState;
 use crate::script::AreaTargeter;
 use crate::*;
 use sulis_core::config::Config;
-use sulis_core::util::{self, gen_rand, invalid_data_error, Point, Size};
+use sulis_core::util::{self, invalid_data_error, Point, Size};
 use sulis_module::area::{PropData, Transition, TriggerKind};
 use sulis_module::{
-    prop, Actor, Area, DamageKind, HitFlags, HitKind, LootList, Module, ObjectSize, Prop, Time,
+    prop, Actor, Area, DamageKind, Encounter, HitFlags, HitKind, LootList, Module, ObjectSize,
+    Prop, Time,
 };
 
 pub struct TriggerState {
@@ -227,6 +228,18 @@ impl AreaState {
         result
     }
 
+    /// Reloads the items in all props and merchants in this area.  See
+    /// `ItemState::reload`
+    pub(crate) fn reload_items(&mut self) {
+        for prop in self.props.iter_mut().flatten() {
+            prop.reload_items();
+        }
+
+        for merchant in self.merchants.iter_mut() {
+            merchant.reload_items();
+        }
+    }
+
     pub fn get_merchant(&self, id: &str) -> Option<&MerchantState> {
         let mut index = None;
         for (i, merchant) in self.merchants.iter().enumerate() {
@@ -279,11 +292,19 @@ impl AreaState {
 
             let location = Location::from_point(&actor_data.location, &area);
             debug!("Adding actor '{}' at '{:?}'", actor.id, location);
-            match self.add_actor(actor, location, Some(unique_id), false, None) {
-                Ok(_) => (),
+            let index = match self.add_actor(actor, location, Some(unique_id), false, None) {
+                Ok(index) => index,
                 Err(e) => {
                     warn!("Error adding actor to area: {}", e);
+                    continue;
                 }
+            };
+
+            if let Some(ref schedule) = actor_data.schedule {
+                let entity = GameState::turn_manager().borrow().entity(index);
+                entity
+                    .borrow_mut()
+                    .set_schedule(Some(Rc::new(schedule.clone())));
             }
         }
 
@@ -313,8 +334,11 @@ impl AreaState {
         self.add_transitions_from_area();
 
         let mut auto_spawn = Vec::with_capacity(self.area.encounters.len());
-        for encounter in &self.area.encounters {
-            auto_spawn.push(encounter.encounter.auto_spawn);
+        for index in 0..self.area.encounters.len() {
+            match self.encounter(index) {
+                None => auto_spawn.push(false),
+                Some(encounter) => auto_spawn.push(encounter.auto_spawn),
+            }
         }
 
         for (index, spawn) in auto_spawn.into_iter().enumerate() {
@@ -505,25 +529,48 @@ impl AreaState {
     }
 
     pub fn spawn_encounter(&mut self, enc_index: usize, respect_debug: bool) {
-        let (actors, point, size, ai_group) = {
-            let enc_data = &self.area.encounters[enc_index];
+        let mgr = GameState::turn_manager();
+        let ai_group = mgr
+            .borrow_mut()
+            .get_next_ai_group(&self.area.area.id, enc_index);
+        if respect_debug && !Config::debug().encounter_spawning {
+            return;
+        }
 
-            let mgr = GameState::turn_manager();
-            let ai_group = mgr
-                .borrow_mut()
-                .get_next_ai_group(&self.area.area.id, enc_index);
-            if respect_debug && !Config::debug().encounter_spawning {
-                return;
-            }
-            let encounter = &enc_data.encounter;
+        self.spawn_encounter_actors(enc_index, ai_group);
+    }
+
+    /// Returns the current definition of the encounter at `enc_index` in this
+    /// area.  The area holds the encounter as it was when the area was
+    /// loaded, which is out of date once encounters have been reloaded.
+    pub(crate) fn encounter(&self, enc_index: usize) -> Option<Rc<Encounter>> {
+        let enc_data = self.area.encounters.get(enc_index)?;
+        let encounter = &enc_data.encounter;
+        Some(Module::encounter(&encounter.id).unwrap_or_else(|| Rc::clone(encounter)))
+    }
+
+    /// Generates and adds the actors for the encounter at `enc_index`, as
+    /// members of the specified AI group.  Returns the indices of the
+    /// added entities.
+    pub(crate) fn spawn_encounter_actors(
+        &mut self,
+        enc_index: usize,
+        ai_group: usize,
+    ) -> Vec<usize> {
+        let (actors, point, size) = {
+            let enc_data = &self.area.encounters[enc_index];
+            let encounter = match self.encounter(enc_index) {
+                None => return Vec::new(),
+                Some(encounter) => encounter,
+            };
             (
-                encounter.gen_actors(),
+                GameState::with_random(|rand| encounter.gen_actors(rand)),
                 enc_data.location,
                 enc_data.size,
-                ai_group,
             )
         };
 
+        let mut indices = Vec::new();
         for (actor, unique_id) in actors {
             let location = match self.gen_location(&actor, point, size) {
                 None => {
@@ -537,7 +584,7 @@ impl AreaState {
             };
 
             match self.add_actor(actor, location, unique_id, false, Some(ai_group)) {
-                Ok(_) => (),
+                Ok(index) => indices.push(index),
                 Err(e) => {
                     warn!(
                         "Error adding actor for spawned encounter: '{}' at {},{}",
@@ -546,6 +593,8 @@ impl AreaState {
                 }
             }
         }
+
+        indices
     }
 
     fn gen_location(&self, actor: &Rc<Actor>, loc: Point, size: Size) -> Option<Location> {
@@ -554,7 +603,7 @@ impl AreaState {
             return None;
         }
 
-        let roll = gen_rand(0, available.len());
+        let roll = GameState::with_random(|rand| rand.gen(0, available.len()));
 
         let point = available[roll];
         let location = Location::from_point(&point, &self.area.area);
@@ -841,6 +890,54 @@ impl AreaState {
         has_visibility(&self.area, &self.prop_vis_grid, parent, target)
     }
 
+    /// Returns true if the target would be visible to an object of the
+    /// specified size at `x`, `y`, false otherwise
+    pub fn has_visibility_at(
+        &self,
+        size: &ObjectSize,
+        x: i32,
+        y: i32,
+        target: &EntityState,
+    ) -> bool {
+        let start_x = x + size.width / 2;
+        let start_y = y + size.height / 2;
+        has_visibility_from(
+            &self.area,
+            &self.prop_vis_grid,
+            start_x,
+            start_y,
+            target.location_points(),
+        )
+    }
+
+    /// Returns true if an object of the specified size at `x`, `y` would be
+    /// visible to the viewer, false otherwise
+    pub fn is_visible_at(&self, viewer: &EntityState, size: &ObjectSize, x: i32, y: i32) -> bool {
+        let start_x = viewer.location.x + viewer.size.width / 2;
+        let start_y = viewer.location.y + viewer.size.height / 2;
+        has_visibility_from(
+            &self.area,
+            &self.prop_vis_grid,
+            start_x,
+            start_y,
+            size.points(x, y),
+        )
+    }
+
+    /// Returns the indices of all surfaces covering any point of an object
+    /// of the specified size at `x`, `y`
+    pub fn surfaces_at(&self, size: &ObjectSize, x: i32, y: i32) -> HashSet<usize> {
+        let mut surfaces = HashSet::new();
+        for p in size.points(x, y) {
+            if !self.area.area.coords_valid(p.x, p.y) {
+                continue;
+            }
+            let index = (p.x + p.y * self.area.width) as usize;
+            surfaces.extend(self.surface_grid[index].iter());
+        }
+        surfaces
+    }
+
     pub fn compute_pc_visibility(
         &mut self,
         entity: &Rc<RefCell<EntityState>>,
@@ -979,7 +1076,8 @@ impl AreaState {
         location: Location,
         temporary: bool,
     ) -> Result<usize, Error> {
-        let prop = &prop_data.prop;
+        let prop_state = PropState::new(prop_data, location, temporary);
+        let (prop, location) = (&prop_state.prop, &prop_state.location);
 
         if !self.area.area.coords_valid(location.x, location.y) {
             return invalid_data_error(&format!("Prop location outside area bounds"));
@@ -992,8 +1090,6 @@ impl AreaState {
             return invalid_data_error(&format!("Prop location outside area bounds"));
         }
 
-        let prop_state = PropState::new(prop_data, location, temporary);
-
         let start_x = prop_state.location.x as usize;
         let start_y = prop_state.location.y as usize;
         let end_x = start_x + prop_state.prop.size.width as usize;
@@ -0,0 +1,248 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! A record of attacks, effects, ability uses, and deaths, with the full
+//! details of each attack roll.  Entries are pushed as they happen by the
+//! game state, and may be viewed by registering a listener or exported to
+//! a text file.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::Error;
+use std::path::Path;
+
+use sulis_module::{AttackRoll, DamageRoll, HitFlags, HitKind, Module, Time};
+
+use crate::{ChangeListener, ChangeListenerList, GameState};
+
+/// The maximum number of entries retained.  The oldest entries are
+/// discarded once this is exceeded.
+pub const MAX_ENTRIES: usize = 1000;
+
+thread_local! {
+    static ENTRIES: RefCell<VecDeque<CombatLogEntry>> = RefCell::new(VecDeque::new());
+    static LISTENERS: RefCell<ChangeListenerList<CombatLogEntry>> =
+        RefCell::new(ChangeListenerList::default());
+}
+
+#[derive(Debug, Clone)]
+pub enum CombatLogKind {
+    Attack {
+        attacker: String,
+        defender: String,
+
+        /// The attack roll, or `None` if the attack missed due to
+        /// concealment before it was rolled
+        roll: Option<AttackRoll>,
+        hit_kind: HitKind,
+        flags: HitFlags,
+        damage: Vec<DamageRoll>,
+    },
+    EffectApplied {
+        target: String,
+        effect: String,
+    },
+    EffectRemoved {
+        target: String,
+        effect: String,
+    },
+    AbilityUsed {
+        entity: String,
+        ability: String,
+    },
+    Death {
+        entity: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct CombatLogEntry {
+    pub time: Time,
+    pub kind: CombatLogKind,
+}
+
+fn hit_kind_str(hit_kind: HitKind) -> &'static str {
+    match hit_kind {
+        HitKind::Miss => "Miss",
+        HitKind::Graze => "Graze",
+        HitKind::Hit => "Hit",
+        HitKind::Crit => "Critical Hit",
+        HitKind::Auto => "Automatic Hit",
+    }
+}
+
+fn fmt_attack(
+    f: &mut fmt::Formatter,
+    roll: &Option<AttackRoll>,
+    hit_kind: HitKind,
+    flags: HitFlags,
+    damage: &[DamageRoll],
+) -> fmt::Result {
+    if let Some(roll) = roll {
+        write!(
+            f,
+            "rolled {} + {} accuracy vs {} defense",
+            roll.roll, roll.accuracy, roll.defense
+        )?;
+        if let Some(crit_roll) = roll.crit_roll {
+            write!(f, ", critical confirm {}", crit_roll)?;
+        }
+        write!(f, ": ")?;
+    }
+
+    write!(f, "{}", hit_kind_str(hit_kind))?;
+    if flags.sneak_attack {
+        write!(f, " (sneak attack)")?;
+    } else if flags.flanking {
+        write!(f, " (flanking)")?;
+    }
+    if flags.concealment {
+        write!(f, " (concealment)")?;
+    }
+
+    if damage.is_empty() {
+        return Ok(());
+    }
+
+    let total: u32 = damage.iter().map(|d| d.amount).sum();
+    write!(f, " for {} damage", total)?;
+
+    let mut first = true;
+    for d in damage {
+        write!(f, "{}", if first { " (" } else { "; " })?;
+        first = false;
+
+        write!(
+            f,
+            "{} {}: rolled {} x{:.2}",
+            d.amount, d.kind, d.rolled, d.multiplier
+        )?;
+        if d.resistance != 0 {
+            write!(f, ", {}% resisted", d.resistance)?;
+        }
+        if d.armor > 0.0 {
+            write!(f, ", {:.1} armor", d.armor)?;
+        }
+    }
+    write!(f, ")")
+}
+
+impl CombatLogEntry {
+    /// The game time of this entry, formatted for display
+    pub fn time_text(&self) -> String {
+        let rules = Module::rules();
+        let hour = rules.get_hour_name(self.time.hour);
+        format!("Day {}, {}, Round {}", self.time.day, hour, self.time.round)
+    }
+}
+
+impl fmt::Display for CombatLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} - {}", self.time_text(), self.kind)
+    }
+}
+
+impl fmt::Display for CombatLogKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use CombatLogKind::*;
+        match self {
+            Attack {
+                attacker,
+                defender,
+                roll,
+                hit_kind,
+                flags,
+                damage,
+            } => {
+                write!(f, "{} attacks {}: ", attacker, defender)?;
+                fmt_attack(f, roll, *hit_kind, *flags, damage)
+            }
+            EffectApplied { target, effect } => write!(f, "{} gains {}", target, effect),
+            EffectRemoved { target, effect } => write!(f, "{} loses {}", target, effect),
+            AbilityUsed { entity, ability } => write!(f, "{} uses {}", entity, ability),
+            Death { entity } => write!(f, "{} is defeated", entity),
+        }
+    }
+}
+
+pub struct CombatLog;
+
+impl CombatLog {
+    /// Adds a new entry at the current game time and notifies all listeners
+    pub(crate) fn push(kind: CombatLogKind) {
+        let time = GameState::turn_manager().borrow().current_time();
+        CombatLog::push_at(time, kind);
+    }
+
+    /// Adds a new entry at the specified time.  Used from within the turn
+    /// manager, where the current time cannot be looked up.
+    pub(crate) fn push_at(time: Time, kind: CombatLogKind) {
+        let entry = CombatLogEntry { time, kind };
+        debug!("Combat log: {}", entry);
+
+        ENTRIES.with(|entries| {
+            let mut entries = entries.borrow_mut();
+            entries.push_back(entry.clone());
+            while entries.len() > MAX_ENTRIES {
+                entries.pop_front();
+            }
+        });
+
+        LISTENERS.with(|listeners| listeners.borrow().notify(&entry));
+    }
+
+    /// Removes all entries.  Called whenever a game is started or loaded.
+    pub(crate) fn clear() {
+        ENTRIES.with(|entries| entries.borrow_mut().clear());
+    }
+
+    /// Returns a copy of all retained entries, oldest first
+    pub fn entries() -> Vec<CombatLogEntry> {
+        ENTRIES.with(|entries| entries.borrow().iter().cloned().collect())
+    }
+
+    /// Adds a listener that is called with each new entry as it is added.
+    /// Replaces any existing listener with the same ID.
+    pub fn add_listener(listener: ChangeListener<CombatLogEntry>) {
+        LISTENERS.with(|listeners| listeners.borrow_mut().add(listener));
+    }
+
+    pub fn remove_listener(id: &str) {
+        LISTENERS.with(|listeners| listeners.borrow_mut().remove(id));
+    }
+
+    /// Writes all retained entries, one per line, to the text file at the
+    /// specified path
+    pub fn export(path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut text = String::new();
+        ENTRIES.with(|entries| {
+            for entry in entries.borrow().iter() {
+                text.push_str(&entry.to_string());
+                text.push('\n');
+            }
+        });
+
+        fs::write(path, text)?;
+        info!("Exported combat log to {:?}", path);
+        Ok(())
+    }
+}
@@ -23,7 +23,10 @@ use std::usize;
 
 use sulis_core::config::Config;
 
+use crate::ai::ScheduleState;
 use crate::animation::{self, Anim};
+use crate::combat_log::{CombatLog, CombatLogKind};
+use crate::event_bus::{EventBus, GameEvent};
 use crate::save_state::EntitySaveState;
 use crate::script::{self, CallbackData, ScriptEntitySet};
 use crate::{
@@ -35,12 +38,20 @@ use sulis_core::ui::{color, Color};
 use sulis_core::util::{invalid_data_error, Point};
 use sulis_module::area::{Transition, MAX_AREA_SIZE};
 use sulis_module::{
-    actor::Faction, ai, Actor, DamageKind, HitKind, Module, ObjectSize, ObjectSizeIterator,
+    actor::Faction, ai, AITemplate, Actor, DamageKind, HitKind, Module, ObjectSize,
+    ObjectSizeIterator, PartyAIPreset, Schedule,
 };
 
 enum AIState {
-    Player { vis: Vec<bool>, show_portrait: bool },
-    AI { group: Option<usize>, active: bool },
+    Player {
+        vis: Vec<bool>,
+        show_portrait: bool,
+        party_ai: Option<PartyAIPreset>,
+    },
+    AI {
+        group: Option<usize>,
+        active: bool,
+    },
 }
 
 pub struct EntityState {
@@ -55,6 +66,7 @@ pub struct EntityState {
 
     ai_state: AIState,
     ai_callbacks: Option<Rc<CallbackData>>,
+    pub(crate) schedule: Option<ScheduleState>,
     pub(crate) marked_for_removal: bool,
     texture_cache_slot: Option<EntityTextureSlot>,
 
@@ -87,6 +99,7 @@ impl EntityState {
                 AIState::Player {
                     vis: vec![false; dim],
                     show_portrait: save.show_portrait,
+                    party_ai: save.party_ai,
                 }
             }
         };
@@ -107,6 +120,12 @@ impl EntityState {
 
         let actor = ActorState::load(save.actor, save.actor_base)?;
 
+        let home = save.schedule_home.unwrap_or_else(|| location.to_point());
+        let random = save.schedule_random;
+        let schedule = save
+            .schedule
+            .map(|schedule| ScheduleState::new(Rc::new(schedule), home, random));
+
         Ok(EntityState {
             actor,
             ai_callbacks: None,
@@ -122,6 +141,7 @@ impl EntityState {
             ai_state,
             marked_for_removal: false,
             texture_cache_slot: None,
+            schedule,
             custom_flags: save.custom_flags,
             collapsed_groups: save.collapsed_groups,
         })
@@ -139,6 +159,7 @@ impl EntityState {
             AIState::Player {
                 vis: vec![false; dim],
                 show_portrait: true,
+                party_ai: None,
             }
         } else {
             AIState::AI {
@@ -154,6 +175,10 @@ impl EntityState {
 
         debug!("Creating new entity state for {}", actor.id);
         let size = Rc::clone(&actor.race.size);
+        let schedule = actor
+            .schedule
+            .as_ref()
+            .map(|schedule| ScheduleState::new(Rc::clone(schedule), location.to_point(), None));
         let actor_state = ActorState::new(actor);
         EntityState {
             actor: actor_state,
@@ -170,11 +195,24 @@ impl EntityState {
             marked_for_removal: false,
             ai_state,
             texture_cache_slot: None,
+            schedule,
             custom_flags: HashMap::new(),
             collapsed_groups: Vec::new(),
         }
     }
 
+    /// Returns the out of combat schedule this entity follows, if any
+    pub fn schedule(&self) -> Option<&Rc<Schedule>> {
+        self.schedule.as_ref().map(|state| state.schedule())
+    }
+
+    /// Sets the out of combat schedule this entity follows, starting from its
+    /// current location, or stops it following one
+    pub fn set_schedule(&mut self, schedule: Option<Rc<Schedule>>) {
+        let home = self.location.to_point();
+        self.schedule = schedule.map(|schedule| ScheduleState::new(schedule, home, None));
+    }
+
     pub fn add_collapsed_group(&mut self, group: String) {
         self.collapsed_groups.push(group);
     }
@@ -332,7 +370,53 @@ impl EntityState {
         self.ai_state = AIState::Player {
             vis: vec![false; dim],
             show_portrait,
+            party_ai: None,
+        };
+    }
+
+    /// Returns the AI preset controlling this party member in combat, or
+    /// `None` if it is controlled by the player.  Always `None` for entities
+    /// outside the party.
+    pub fn party_ai(&self) -> Option<PartyAIPreset> {
+        match self.ai_state {
+            AIState::Player { party_ai, .. } => party_ai,
+            AIState::AI { .. } => None,
+        }
+    }
+
+    /// Sets the AI preset controlling this party member in combat.  Has no
+    /// effect on entities outside the party.
+    pub fn set_party_ai(&mut self, preset: Option<PartyAIPreset>) {
+        match self.ai_state {
+            AIState::Player {
+                ref mut party_ai, ..
+            } => *party_ai = preset,
+            AIState::AI { .. } => return,
+        }
+        self.listeners.notify(self);
+    }
+
+    /// Returns the AI template this entity acts with.  For party members,
+    /// this is the template for their AI preset, if they have one.
+    pub fn ai_template(&self) -> Option<Rc<AITemplate>> {
+        if !self.is_party_member() {
+            return self.actor.actor.ai.clone();
+        }
+
+        let preset = self.party_ai()?;
+        let id = match Module::rules().party_ai.get(&preset) {
+            None => {
+                warn!("No AI template specified for party AI {:?}", preset);
+                return None;
+            }
+            Some(id) => id.to_string(),
         };
+
+        let template = Module::ai_template(&id);
+        if template.is_none() {
+            warn!("Invalid AI template '{}' for party AI {:?}", id, preset);
+        }
+        template
     }
 
     pub fn remove_from_party(&mut self) {
@@ -342,6 +426,26 @@ impl EntityState {
         };
     }
 
+    /// Replaces this entity's actor, items, and abilities with their current
+    /// definitions, such as after `Module::reload_resources`.  Party members
+    /// keep their levels and abilities, while other entities use the actor
+    /// definition from the module.
+    pub(crate) fn reload_resources(&mut self) {
+        let actor = match Module::actor(&self.actor.actor.id) {
+            Some(actor) if !self.is_party_member() => actor,
+            _ => match Module::load_actor(self.actor.actor.to_builder()) {
+                Err(e) => {
+                    warn!("Unable to reload actor for '{}'", self.unique_id());
+                    warn!("{}", e);
+                    return;
+                }
+                Ok(actor) => Rc::new(actor),
+            },
+        };
+
+        self.actor.reload_resources(actor);
+    }
+
     pub fn is_party_member(&self) -> bool {
         match self.ai_state {
             AIState::Player { .. } => true,
@@ -349,6 +453,15 @@ impl EntityState {
         }
     }
 
+    /// Returns true if this is a party member whose combat turns are
+    /// played by the player rather than a party AI preset
+    pub fn is_player_controlled(&self) -> bool {
+        match self.ai_state {
+            AIState::Player { party_ai, .. } => party_ai.is_none(),
+            AIState::AI { .. } => false,
+        }
+    }
+
     pub fn clear_pc_vis(&mut self) {
         match self.ai_state {
             AIState::Player { ref mut vis, .. } => unsafe {
@@ -469,8 +582,16 @@ impl EntityState {
         damage: Vec<(DamageKind, u32)>,
     ) {
         let hp_amount = damage.iter().map(|(_, amount)| amount).sum();
+        let was_alive = entity.borrow().actor.hp() > 0;
         entity.borrow_mut().actor.remove_hp(hp_amount);
 
+        EventBus::publish(GameEvent::EntityDamaged {
+            entity: entity.borrow().index(),
+            attacker: attacker.borrow().index(),
+            hit_kind,
+            damage: damage.clone(),
+        });
+
         let targets = ScriptEntitySet::from_pair(entity, attacker);
 
         let mgr = GameState::turn_manager();
@@ -485,6 +606,14 @@ impl EntityState {
                 "Entity '{}' has zero hit points.  Playing death animation",
                 entity.borrow().actor.actor.name
             );
+            if was_alive {
+                CombatLog::push(CombatLogKind::Death {
+                    entity: entity.borrow().actor.actor.name.to_string(),
+                });
+                EventBus::publish(GameEvent::EntityDied {
+                    entity: entity.borrow().index(),
+                });
+            }
             let anim = Anim::new_entity_death(entity);
             GameState::add_animation(anim);
         } else {
@@ -0,0 +1,163 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! A single stream of typed gameplay events that any part of the game may
+//! subscribe to.  Events are queued as they happen, and delivered to all
+//! subscribers once per frame from `GameState::update`.  Since delivery
+//! happens outside of any game state borrows, subscribers are free to query
+//! and modify the game state.
+//!
+//! Entities are referred to by their index in the turn manager, which may
+//! be looked up with `TurnManager::entity_checked`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sulis_module::on_trigger::QuestEntryState;
+use sulis_module::{DamageKind, HitKind};
+
+use crate::ChangeListener;
+
+thread_local! {
+    static QUEUE: RefCell<Vec<GameEvent>> = RefCell::new(Vec::new());
+    static SUBSCRIBERS: RefCell<Vec<Rc<ChangeListener<GameEvent>>>> = RefCell::new(Vec::new());
+}
+
+/// The names of all kinds of events, as returned by `GameEvent::name`
+pub const EVENT_NAMES: [&str; 10] = [
+    "entity_damaged",
+    "entity_died",
+    "effect_applied",
+    "effect_removed",
+    "ability_activated",
+    "item_looted",
+    "quest_state_changed",
+    "area_entered",
+    "combat_started",
+    "combat_ended",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    EntityDamaged {
+        entity: usize,
+        attacker: usize,
+        hit_kind: HitKind,
+        damage: Vec<(DamageKind, u32)>,
+    },
+    EntityDied {
+        entity: usize,
+    },
+    EffectApplied {
+        entity: usize,
+        effect: usize,
+        name: String,
+    },
+    EffectRemoved {
+        entity: usize,
+        effect: usize,
+        name: String,
+    },
+    AbilityActivated {
+        entity: usize,
+        ability: String,
+    },
+    ItemLooted {
+        item: String,
+        quantity: u32,
+    },
+    QuestStateChanged {
+        quest: String,
+
+        /// The quest entry that changed, or `None` if the state of the
+        /// quest as a whole changed
+        entry: Option<String>,
+        state: QuestEntryState,
+    },
+    AreaEntered {
+        area: String,
+    },
+    CombatStarted,
+    CombatEnded,
+}
+
+impl GameEvent {
+    /// Returns the snake case name of this kind of event, such as
+    /// `entity_damaged`
+    pub fn name(&self) -> &'static str {
+        use GameEvent::*;
+        match self {
+            EntityDamaged { .. } => "entity_damaged",
+            EntityDied { .. } => "entity_died",
+            EffectApplied { .. } => "effect_applied",
+            EffectRemoved { .. } => "effect_removed",
+            AbilityActivated { .. } => "ability_activated",
+            ItemLooted { .. } => "item_looted",
+            QuestStateChanged { .. } => "quest_state_changed",
+            AreaEntered { .. } => "area_entered",
+            CombatStarted => "combat_started",
+            CombatEnded => "combat_ended",
+        }
+    }
+}
+
+pub struct EventBus;
+
+impl EventBus {
+    /// Queues the specified event, to be delivered to all subscribers on
+    /// the next call to `dispatch`
+    pub(crate) fn publish(event: GameEvent) {
+        QUEUE.with(|queue| queue.borrow_mut().push(event));
+    }
+
+    /// Delivers all queued events, in the order they were published, to
+    /// every subscriber.  Events published by subscribers while this is
+    /// running are delivered on the next call.
+    pub(crate) fn dispatch() {
+        let events: Vec<GameEvent> = QUEUE.with(|queue| queue.borrow_mut().drain(..).collect());
+        if events.is_empty() {
+            return;
+        }
+
+        let subscribers: Vec<_> = SUBSCRIBERS.with(|subs| subs.borrow().clone());
+        for event in events {
+            trace!("Dispatching event {:?}", event);
+            for subscriber in subscribers.iter() {
+                subscriber.call(&event);
+            }
+        }
+    }
+
+    /// Discards all queued events.  Called whenever a game is started or
+    /// loaded.
+    pub(crate) fn clear() {
+        QUEUE.with(|queue| queue.borrow_mut().clear());
+    }
+
+    /// Adds a subscriber that will receive every event.  Replaces any
+    /// existing subscriber with the same ID.
+    pub fn subscribe(listener: ChangeListener<GameEvent>) {
+        SUBSCRIBERS.with(|subs| {
+            let mut subs = subs.borrow_mut();
+            subs.retain(|sub| sub.id() != listener.id());
+            subs.push(Rc::new(listener));
+        });
+    }
+
+    pub fn unsubscribe(id: &str) {
+        SUBSCRIBERS.with(|subs| subs.borrow_mut().retain(|sub| sub.id() != id));
+    }
+}
@@ -22,19 +22,24 @@ use std::time;
 
 use sulis_core::config::Config;
 use sulis_core::io::GraphicsRenderer;
-use sulis_core::util::{self, invalid_data_error, ExtInt, Point};
+use sulis_core::util::{self, invalid_data_error, ExtInt, Point, ReproducibleRandom};
 use sulis_module::on_trigger::QuestEntryState;
 use sulis_module::{
     area::{PathFinder, ToKind, Trigger, TriggerKind},
-    Actor, Module, ObjectSize, OnTrigger, Time, MOVE_TO_THRESHOLD,
+    Actor, Difficulty, Module, ObjectSize, OnTrigger, Time, MOVE_TO_THRESHOLD,
 };
 
 use crate::animation::{self, particle_generator::Param, Anim, AnimSaveState, AnimState};
-use crate::script::{script_cache, script_callback, Script, ScriptCallback, ScriptEntity};
+use crate::script::{
+    script_cache, script_callback, Script, ScriptCallback, ScriptEntity, ScriptSubscriptions,
+};
 use crate::{
-    path_finder::find_path, AreaState, ChangeListener, ChangeListenerList, Effect, EntityState,
-    Formation, ItemList, ItemState, Location, PartyStash, QuestStateSet, SaveState, TurnManager,
-    UICallback, WorldMapState, AI,
+    ai,
+    path_finder::find_path,
+    save_file::{self, AutosaveTrigger},
+    AreaState, ChangeListener, ChangeListenerList, CombatLog, Effect, EntityState, EventBus,
+    Formation, GameEvent, ItemList, ItemState, Location, PartyStash, QuestStateSet, SaveState,
+    TurnManager, UICallback, WorldMapState, AI,
 };
 
 thread_local! {
@@ -45,6 +50,19 @@ thread_local! {
     static MODAL_LOCKED: Cell<bool> = Cell::new(false);
     static ANIMATIONS: RefCell<AnimState> = RefCell::new(AnimState::new());
     static ANIMS_TO_ADD: RefCell<Vec<Anim>> = RefCell::new(Vec::new());
+    static RANDOM: RefCell<ReproducibleRandom> = RefCell::new(ReproducibleRandom::new(None));
+    static PLAY_TIME_MILLIS: Cell<u64> = Cell::new(0);
+    static IRONMAN: Cell<bool> = Cell::new(false);
+    static DIFFICULTY: RefCell<Option<Rc<Difficulty>>> = RefCell::new(None);
+}
+
+/// The options chosen by the player when starting a new game
+#[derive(Debug, Clone, Default)]
+pub struct NewGameOptions {
+    pub ironman: bool,
+
+    /// The ID of the difficulty preset, or `None` for the rules default
+    pub difficulty: Option<String>,
 }
 
 pub struct GameState {
@@ -80,6 +98,22 @@ impl GameState {
         MODAL_LOCKED.with(|c| c.set(false));
         ANIMS_TO_ADD.with(|anims| anims.borrow_mut().clear());
         AI.with(|ai| *ai.borrow_mut() = AI::new());
+        let random = match save_state.random {
+            Some(random) => random,
+            None => ReproducibleRandom::new(None),
+        };
+        info!(
+            "Loaded gameplay random generator with seed {}",
+            random.seed()
+        );
+        RANDOM.with(|r| *r.borrow_mut() = random);
+        let play_time_millis = save_state.play_time_millis;
+        PLAY_TIME_MILLIS.with(|t| t.set(play_time_millis));
+        let ironman = save_state.ironman;
+        IRONMAN.with(|i| i.set(ironman));
+        let difficulty = Module::rules().difficulty(save_state.difficulty.as_deref());
+        DIFFICULTY.with(|d| *d.borrow_mut() = Some(Rc::new(difficulty)));
+        let script_subscriptions = save_state.script_subscriptions;
         script_cache::setup().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
 
         let game_state: Result<GameState, Error> = {
@@ -259,6 +293,9 @@ impl GameState {
         STATE.with(|state| {
             *state.borrow_mut() = Some(game_state);
         });
+        CombatLog::clear();
+        EventBus::clear();
+        ScriptSubscriptions::load(script_subscriptions);
 
         let pc = GameState::player();
         let area_state = GameState::area_state();
@@ -269,19 +306,41 @@ impl GameState {
         Ok(())
     }
 
-    pub fn init(pc_actor: Rc<Actor>) -> Result<(), Error> {
+    pub fn init(pc_actor: Rc<Actor>, options: NewGameOptions) -> Result<(), Error> {
+        let ironman = options.ironman;
         TURN_MANAGER.with(|mgr| {
             let rules = Module::rules();
             let starting_time = Module::campaign().starting_time;
             mgr.borrow_mut().load(rules.compute_millis(starting_time));
         });
 
+        let random = ReproducibleRandom::new(None);
+        info!(
+            "Created gameplay random generator with seed {}",
+            random.seed()
+        );
+        RANDOM.with(|r| *r.borrow_mut() = random);
+        PLAY_TIME_MILLIS.with(|t| t.set(0));
+        IRONMAN.with(|i| i.set(ironman));
+        let difficulty = Module::rules().difficulty(options.difficulty.as_deref());
+        info!("Starting new game on difficulty '{}'", difficulty.id);
+        DIFFICULTY.with(|d| *d.borrow_mut() = Some(Rc::new(difficulty)));
+        save_file::reset_autosave();
+        CombatLog::clear();
+        EventBus::clear();
+        ScriptSubscriptions::load(Vec::new());
+
         script_cache::setup().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
         let game_state = GameState::new(pc_actor)?;
         STATE.with(|state| {
             *state.borrow_mut() = Some(game_state);
         });
 
+        // give ironman games their save slot from the start
+        if ironman {
+            save_file::request_autosave(AutosaveTrigger::AreaTransition);
+        }
+
         let pc = GameState::player();
         let area_state = GameState::area_state();
         area_state.borrow_mut().update_view_visibility();
@@ -289,6 +348,9 @@ impl GameState {
             .borrow_mut()
             .push_scroll_to_callback(Rc::clone(&pc));
         area_state.borrow_mut().on_load_fired = true;
+        EventBus::publish(GameEvent::AreaEntered {
+            area: area_state.borrow().area.area.id.to_string(),
+        });
         let area_state = area_state.borrow();
         GameState::add_ui_callbacks_of_kind(
             &area_state.area.area.triggers,
@@ -448,6 +510,12 @@ impl GameState {
             let state = state.as_mut().unwrap();
             state.quests.set_state(&quest, entry_state);
         });
+
+        EventBus::publish(GameEvent::QuestStateChanged {
+            quest,
+            entry: None,
+            state: entry_state,
+        });
     }
 
     pub fn set_quest_entry_state(quest: String, entry: String, entry_state: QuestEntryState) {
@@ -455,7 +523,13 @@ impl GameState {
             let mut state = state.borrow_mut();
             let state = state.as_mut().unwrap();
             state.quests.set_entry_state(&quest, &entry, entry_state);
-        })
+        });
+
+        EventBus::publish(GameEvent::QuestStateChanged {
+            quest,
+            entry: Some(entry),
+            state: entry_state,
+        });
     }
 
     pub fn set_user_zoom(mut zoom: f32) {
@@ -984,6 +1058,13 @@ impl GameState {
             .borrow_mut()
             .push_scroll_to_callback(Rc::clone(&pc));
 
+        if let Some(area_id) = area_id {
+            save_file::request_autosave(AutosaveTrigger::AreaTransition);
+            EventBus::publish(GameEvent::AreaEntered {
+                area: area_id.to_string(),
+            });
+        }
+
         let mut area_state = area_state.borrow_mut();
         area_state.update_view_visibility();
         if !area_state.on_load_fired {
@@ -1047,7 +1128,10 @@ impl GameState {
             }
         };
 
-        let state = AreaState::new(area, None)?;
+        // seed newly generated areas from the gameplay generator, so they are
+        // reproducible along with everything else
+        let seed = GameState::with_random(|rand| rand.gen(0, u64::MAX)) as u128;
+        let state = AreaState::new(area, Some(seed))?;
         let area_state = Rc::new(RefCell::new(state));
         area_state.borrow_mut().populate();
 
@@ -1095,6 +1179,15 @@ impl GameState {
         })
     }
 
+    /// Returns true if there are UI callbacks that have not yet been
+    /// handled by `update`
+    pub fn has_ui_callbacks() -> bool {
+        STATE.with(|s| match s.borrow().as_ref() {
+            None => false,
+            Some(state) => !state.ui_callbacks.is_empty(),
+        })
+    }
+
     pub fn is_modal_locked() -> bool {
         MODAL_LOCKED.with(|c| c.get())
     }
@@ -1111,6 +1204,37 @@ impl GameState {
         CLEAR_ANIMS.with(|c| c.set(true));
     }
 
+    /// Reloads abilities, items, loot lists, props, conversations, actors,
+    /// and encounters whose YAML has changed, with
+    /// `Module::reload_resources`, and then updates all entities, props,
+    /// merchants, and the party stash to use the new definitions.  Returns
+    /// the number of resources that changed.
+    pub fn reload_resources() -> Result<usize, Error> {
+        let changed = Module::reload_resources()?;
+        for (kind, id) in changed.iter() {
+            info!("Reloading {:?} '{}'", kind, id);
+        }
+
+        if changed.is_empty() {
+            return Ok(0);
+        }
+
+        let mgr = GameState::turn_manager();
+        for entity in mgr.borrow().entity_iter() {
+            entity.borrow_mut().reload_resources();
+        }
+
+        for id in GameState::area_state_ids() {
+            if let Some(area_state) = GameState::get_area_state(&id) {
+                area_state.borrow_mut().reload_items();
+            }
+        }
+
+        GameState::party_stash().borrow_mut().reload_items();
+
+        Ok(changed.len())
+    }
+
     pub fn area_state_ids() -> Vec<String> {
         STATE.with(|s| {
             s.borrow()
@@ -1136,6 +1260,8 @@ impl GameState {
 
     #[must_use]
     pub fn update(millis: u32) -> Option<UICallback> {
+        PLAY_TIME_MILLIS.with(|t| t.set(t.get() + u64::from(millis)));
+
         let ui_cb = STATE.with(|s| {
             let mut state = s.borrow_mut();
             let state = state.as_mut().unwrap();
@@ -1184,8 +1310,12 @@ impl GameState {
             });
         }
 
+        ai::update_schedules(millis);
+
         GameState::handle_disabled_party_members();
 
+        EventBus::dispatch();
+
         let campaign = Module::campaign();
         if let Some(script_data) = &campaign.on_tick_script {
             script_cache::set_report_enabled(false);
@@ -1249,6 +1379,55 @@ impl GameState {
         });
     }
 
+    /// Calls the specified function with the gameplay random number generator.
+    /// All random rolls affecting the game state should use this generator, so
+    /// that they are reproducible from a given save.  The function must not
+    /// call `with_random` itself.
+    pub fn with_random<T, F: FnOnce(&mut ReproducibleRandom) -> T>(f: F) -> T {
+        RANDOM.with(|r| f(&mut r.borrow_mut()))
+    }
+
+    /// The total time spent playing the current game, including previous
+    /// sessions before it was saved
+    pub fn play_time_millis() -> u64 {
+        PLAY_TIME_MILLIS.with(|t| t.get())
+    }
+
+    /// Returns true if the current game is in ironman mode, with a single
+    /// save slot that is autosaved and deleted when the party is defeated
+    pub fn is_ironman() -> bool {
+        IRONMAN.with(|i| i.get())
+    }
+
+    /// The current difficulty preset.  Outside of a game, this is the
+    /// rules default.
+    pub fn difficulty() -> Rc<Difficulty> {
+        DIFFICULTY.with(|d| {
+            Rc::clone(
+                d.borrow_mut()
+                    .get_or_insert_with(|| Rc::new(Module::rules().difficulty(None))),
+            )
+        })
+    }
+
+    /// Changes the difficulty of the current game to the preset with the
+    /// specified ID, recomputing the stats of all entities
+    pub fn set_difficulty(id: &str) {
+        let difficulty = Module::rules().difficulty(Some(id));
+        info!("Setting difficulty to '{}'", difficulty.id);
+        DIFFICULTY.with(|d| *d.borrow_mut() = Some(Rc::new(difficulty)));
+
+        let mgr = GameState::turn_manager();
+        let entities: Vec<_> = mgr.borrow().entity_iter().collect();
+        for entity in entities {
+            entity.borrow_mut().actor.compute_stats();
+        }
+    }
+
+    pub fn save_random() -> ReproducibleRandom {
+        RANDOM.with(|r| r.borrow().clone())
+    }
+
     pub fn save_anims() -> Vec<AnimSaveState> {
         ANIMATIONS.with(|a| a.borrow().save_anims())
     }
@@ -1260,16 +1439,23 @@ impl GameState {
         mgr.is_combat_active()
     }
 
-    /// Returns true if the PC has the current turn, false otherwise
+    /// Returns true if the PC has the current turn, false otherwise.  Party
+    /// members whose turn is played by a party AI preset do not count.
     pub fn is_pc_current() -> bool {
         let mgr = GameState::turn_manager();
         if let Some(entity) = mgr.borrow().current() {
-            return entity.borrow().is_party_member();
+            return entity.borrow().is_player_controlled();
         }
 
         false
     }
 
+    /// Returns true if the specified entity has the current turn and that
+    /// turn is being played by its party AI preset, false otherwise
+    pub fn is_party_ai_current(entity: &Rc<RefCell<EntityState>>) -> bool {
+        GameState::is_current(entity) && entity.borrow().party_ai().is_some()
+    }
+
     pub fn is_current(entity: &Rc<RefCell<EntityState>>) -> bool {
         let mgr = GameState::turn_manager();
         if let Some(current) = mgr.borrow().current() {
@@ -1347,12 +1533,39 @@ impl GameState {
         dist: f32,
         cb: Option<Box<dyn ScriptCallback>>,
     ) -> bool {
-        if entity.borrow().actor.stats.move_disabled {
+        // if entity cannot move even 1 square
+        if entity.borrow().actor.ap() < entity.borrow().actor.get_move_ap_cost(1) {
             return false;
         }
 
-        // if entity cannot move even 1 square
-        if entity.borrow().actor.ap() < entity.borrow().actor.get_move_ap_cost(1) {
+        GameState::start_move(entity, entities_to_ignore, x, y, dist, cb)
+    }
+
+    /// Moves the entity towards the specified point outside of combat,
+    /// where movement does not need or spend action points.  Returns false
+    /// if combat is active or no path could be found.
+    pub(crate) fn move_out_of_combat(
+        entity: &Rc<RefCell<EntityState>>,
+        x: f32,
+        y: f32,
+        dist: f32,
+    ) -> bool {
+        if GameState::is_combat_active() {
+            return false;
+        }
+
+        GameState::start_move(entity, Vec::new(), x, y, dist, None)
+    }
+
+    fn start_move(
+        entity: &Rc<RefCell<EntityState>>,
+        entities_to_ignore: Vec<usize>,
+        x: f32,
+        y: f32,
+        dist: f32,
+        cb: Option<Box<dyn ScriptCallback>>,
+    ) -> bool {
+        if entity.borrow().actor.stats.move_disabled {
             return false;
         }
 
@@ -181,6 +181,17 @@ impl Inventory {
         }
     }
 
+    /// Reloads each equipped and quick slot item.  See `ItemState::reload`
+    pub fn reload_items(&mut self) {
+        for item in self.equipped.values_mut() {
+            item.reload();
+        }
+
+        for item in self.quick.values_mut() {
+            item.reload();
+        }
+    }
+
     pub fn swap_weapon_set(&mut self) {
         let cur_main = self.equipped.remove(&Slot::HeldMain);
         let cur_off = self.equipped.remove(&Slot::HeldOff);
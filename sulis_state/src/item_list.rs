@@ -40,6 +40,13 @@ impl ItemList {
         self.items.clear();
     }
 
+    /// Reloads each item in this list.  See `ItemState::reload`
+    pub fn reload_items(&mut self) {
+        for (_, item) in self.items.iter_mut() {
+            item.reload();
+        }
+    }
+
     pub fn iter<'a>(&'a self) -> Iter<'a, (u32, ItemState)> {
         self.items.iter()
     }
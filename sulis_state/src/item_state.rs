@@ -14,7 +14,7 @@
 //  You should have received a copy of the GNU General Public License
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
-use sulis_module::{Item, Module};
+use sulis_module::{Item, ItemSaveState, Module};
 
 use std::rc::Rc;
 
@@ -40,4 +40,14 @@ impl ItemState {
             Some(item) => Some(ItemState::new(item)),
         }
     }
+
+    /// Replaces this item with its current definition in the module, such as
+    /// after `Module::reload_resources`.  If the item no longer exists, it is
+    /// left unchanged.
+    pub fn reload(&mut self) {
+        let save = ItemSaveState::new(&self.item);
+        if let Some(item) = Module::create_get_item(&save.id, &save.adjectives) {
+            self.item = item;
+        }
+    }
 }
@@ -40,6 +40,12 @@ mod change_listener;
 pub use self::change_listener::ChangeListener;
 pub use self::change_listener::ChangeListenerList;
 
+pub mod combat_log;
+pub use self::combat_log::CombatLog;
+
+pub mod event_bus;
+pub use self::event_bus::{EventBus, GameEvent};
+
 mod effect;
 pub use self::effect::Effect;
 
@@ -56,6 +62,7 @@ pub use self::formation::Formation;
 
 mod game_state;
 pub use self::game_state::GameState;
+pub use self::game_state::NewGameOptions;
 
 mod generated_area;
 pub use self::generated_area::{GeneratedArea, PregenOutput};
@@ -74,7 +81,7 @@ pub use self::location::Location;
 
 mod los_calculator;
 pub use self::los_calculator::calculate_los;
-pub use self::los_calculator::has_visibility;
+pub use self::los_calculator::{has_visibility, has_visibility_from};
 
 mod merchant_state;
 pub use self::merchant_state::MerchantState;
@@ -101,6 +108,9 @@ pub mod save_file;
 pub use self::save_file::SaveFile;
 pub use self::save_file::SaveFileMetaData;
 
+pub mod save_migration;
+pub use self::save_migration::CURRENT_SAVE_VERSION;
+
 mod save_state;
 pub use self::save_state::SaveState;
 
@@ -114,6 +124,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+use sulis_core::util::ActiveResources;
 use sulis_module::{Actor, Module, OnTrigger};
 
 #[derive(Debug)]
@@ -121,13 +132,19 @@ pub enum NextGameStep {
     Exit,
     NewCampaign {
         pc_actor: Rc<Actor>,
+        options: NewGameOptions,
     },
     LoadCampaign {
         save_state: SaveState,
     },
+    LoadResourcesAndCampaign {
+        resources: ActiveResources,
+        save_file: SaveFileMetaData,
+    },
     LoadModuleAndNewCampaign {
         pc_actor: Rc<Actor>,
         module_dir: String,
+        options: NewGameOptions,
     },
     MainMenu,
     MainMenuReloadResources,
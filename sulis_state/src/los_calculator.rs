@@ -18,6 +18,7 @@ use std::cmp;
 use std::collections::HashSet;
 
 use crate::{EntityState, GeneratedArea};
+use sulis_core::util::Point;
 
 #[must_use]
 pub fn calculate_los(
@@ -86,15 +87,27 @@ pub fn has_visibility(
 ) -> bool {
     let start_x = entity.location.x + entity.size.width / 2;
     let start_y = entity.location.y + entity.size.height / 2;
-    let src_elev = area.layer_set.elevation(start_x, start_y);
+    has_visibility_from(
+        area,
+        prop_vis_grid,
+        start_x,
+        start_y,
+        target.location_points(),
+    )
+}
 
-    for p in target.location_points() {
-        if check_vis(area, prop_vis_grid, start_x, start_y, p.x, p.y, src_elev) {
-            return true;
-        }
-    }
+/// Returns true if any of the `points` are visible from the specified
+/// start position, false otherwise
+pub fn has_visibility_from(
+    area: &GeneratedArea,
+    prop_vis_grid: &[bool],
+    start_x: i32,
+    start_y: i32,
+    mut points: impl Iterator<Item = Point>,
+) -> bool {
+    let src_elev = area.layer_set.elevation(start_x, start_y);
 
-    false
+    points.any(|p| check_vis(area, prop_vis_grid, start_x, start_y, p.x, p.y, src_elev))
 }
 
 fn check_vis(
@@ -72,7 +72,7 @@ impl MerchantState {
 
         let mut items = ItemList::new();
 
-        for (qty, item) in loot_list.generate() {
+        for (qty, item) in GameState::with_random(|rand| loot_list.generate(rand)) {
             let item_state = ItemState::new(item);
             items.add_quantity(qty, item_state);
         }
@@ -124,7 +124,7 @@ impl MerchantState {
         };
 
         self.items.clear();
-        for (qty, item) in loot_list.generate() {
+        for (qty, item) in GameState::with_random(|rand| loot_list.generate(rand)) {
             self.items.add_quantity(qty, ItemState::new(item));
         }
     }
@@ -154,6 +154,12 @@ impl MerchantState {
         result
     }
 
+    /// Reloads all items for sale.  See `ItemState::reload`
+    pub(crate) fn reload_items(&mut self) {
+        self.items.reload_items();
+        self.listeners.notify(self);
+    }
+
     pub fn items(&self) -> &ItemList {
         &self.items
     }
@@ -17,6 +17,7 @@
 use std::collections::HashMap;
 use std::rc::Rc;
 
+use crate::GameState;
 use sulis_core::util::ExtInt;
 use sulis_module::{Ability, Actor, Class, Faction, Module, StatList};
 
@@ -237,7 +238,7 @@ impl PStats {
     }
 
     pub fn add_xp(&mut self, xp: u32, actor: &Rc<Actor>) {
-        let factor = Module::rules().experience_factor;
+        let factor = Module::rules().experience_factor * GameState::difficulty().experience_factor;
         self.xp += (xp as f32 * factor) as u32;
         self.recompute_level_up(actor);
     }
@@ -14,7 +14,7 @@
 //  You should have received a copy of the GNU General Public License
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
-use crate::{ChangeListenerList, GameState, ItemList, ItemState};
+use crate::{ChangeListenerList, EventBus, GameEvent, GameState, ItemList, ItemState};
 use sulis_module::{ItemListEntrySaveState, Module};
 
 pub struct PartyStash {
@@ -40,6 +40,12 @@ impl PartyStash {
             .collect()
     }
 
+    /// Reloads all items in the stash.  See `ItemState::reload`
+    pub(crate) fn reload_items(&mut self) {
+        self.items.reload_items();
+        self.listeners.notify(self);
+    }
+
     pub fn items(&self) -> &ItemList {
         &self.items
     }
@@ -100,6 +106,7 @@ impl PartyStash {
             let mut i = num_items - 1;
             loop {
                 if let Some((qty, item_state)) = prop_state.remove_all_at(i) {
+                    publish_looted(qty, &item_state);
                     self.add_item(qty, item_state);
                 }
 
@@ -120,9 +127,17 @@ impl PartyStash {
         let prop_state = area_state.get_prop_mut(prop_index);
 
         if let Some((qty, item_state)) = prop_state.remove_all_at(item_index) {
+            publish_looted(qty, &item_state);
             self.add_item(qty, item_state);
         }
 
         self.listeners.notify(&self);
     }
 }
+
+fn publish_looted(quantity: u32, item_state: &ItemState) {
+    EventBus::publish(GameEvent::ItemLooted {
+        item: item_state.item.id.to_string(),
+        quantity,
+    });
+}
@@ -14,6 +14,7 @@
 //  You should have received a copy of the GNU General Public License
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
+use std::collections::VecDeque;
 use std::f32;
 
 use crate::{AreaState, EntityState};
@@ -83,6 +84,53 @@ impl<'a, 'b> LocationChecker for StateLocationChecker<'a, 'b> {
     }
 }
 
+/// Finds every position the entity could move to within `max_moves`
+/// squares, using the same passability rules as path finding.  Returns each
+/// position along with the number of squares needed to reach it, starting
+/// with the entity's current position.
+pub fn reachable_points(
+    area_state: &AreaState,
+    entity: &EntityState,
+    max_moves: u32,
+) -> Vec<(Point, u32)> {
+    let checker = StateLocationChecker::new(area_state, entity, Vec::new());
+    let width = area_state.area.width;
+    let height = area_state.area.height;
+
+    let start = Point::new(entity.location.x, entity.location.y);
+    let mut visited = vec![false; (width * height) as usize];
+    visited[(start.x + start.y * width) as usize] = true;
+
+    let mut result = Vec::new();
+    let mut open = VecDeque::new();
+    open.push_back((start, 0));
+    while let Some((p, moves)) = open.pop_front() {
+        result.push((p, moves));
+        if moves == max_moves {
+            continue;
+        }
+
+        for (dx, dy) in &[(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let (x, y) = (p.x + dx, p.y + dy);
+            if x < 0 || y < 0 || x >= width || y >= height {
+                continue;
+            }
+
+            let index = (x + y * width) as usize;
+            if visited[index] {
+                continue;
+            }
+            visited[index] = true;
+
+            if checker.passable(x, y) {
+                open.push_back((Point::new(x, y), moves + 1));
+            }
+        }
+    }
+
+    result
+}
+
 pub fn find_path(
     path_finder: &mut PathFinder,
     area_state: &AreaState,
@@ -26,7 +26,7 @@ use sulis_module::{prop, Item, LootList, Module, ObjectSizeIterator, Prop};
 
 use crate::entity_state::AreaDrawable;
 use crate::save_state::PropInteractiveSaveState;
-use crate::{ChangeListenerList, EntityTextureCache, ItemList, ItemState, Location};
+use crate::{ChangeListenerList, EntityTextureCache, GameState, ItemList, ItemState, Location};
 
 #[derive(Debug)]
 pub enum Interactive {
@@ -64,6 +64,9 @@ impl fmt::Debug for PropState {
 
 impl PropState {
     pub(crate) fn new(prop_data: &PropData, location: Location, temporary: bool) -> PropState {
+        // the prop data may be from an area loaded before the prop was reloaded
+        let prop = Module::prop(&prop_data.prop.id).unwrap_or_else(|| Rc::clone(&prop_data.prop));
+
         let mut items = ItemList::new();
         for item_save in prop_data.items.iter() {
             let quantity = item_save.quantity;
@@ -83,7 +86,7 @@ impl PropState {
 
         let mut anim_state = AnimationState::default();
 
-        let interactive = match prop_data.prop.interactive {
+        let interactive = match prop.interactive {
             prop::Interactive::Hover => {
                 let text = prop_data.hover_text.clone().unwrap_or(String::new());
                 Interactive::Hover { text }
@@ -111,7 +114,7 @@ impl PropState {
         };
 
         PropState {
-            prop: Rc::clone(&prop_data.prop),
+            prop,
             enabled: prop_data.enabled,
             location,
             interactive,
@@ -291,7 +294,7 @@ impl PropState {
                 };
 
                 info!("Generating loot for prop from '{}'", loot.id);
-                let generated_items = loot.generate();
+                let generated_items = GameState::with_random(|rand| loot.generate(rand));
                 for (qty, item) in generated_items {
                     let item_state = ItemState::new(item);
                     items.add_quantity(qty, item_state);
@@ -317,6 +320,26 @@ impl PropState {
         self.listeners.notify(&self);
     }
 
+    /// Reloads the items and loot list of this prop, if it is a container.
+    /// See `ItemState::reload`
+    pub(crate) fn reload_items(&mut self) {
+        if let Interactive::Container {
+            ref mut items,
+            ref mut loot_to_generate,
+            ..
+        } = self.interactive
+        {
+            items.reload_items();
+
+            if let Some(loot) = loot_to_generate {
+                if let Some(new_loot) = Module::loot_list(&loot.id) {
+                    *loot = new_loot;
+                }
+            }
+        }
+        self.listeners.notify(self);
+    }
+
     pub fn add_items(&mut self, items_to_add: Vec<(u32, Rc<Item>)>) {
         match self.interactive {
             Interactive::Container { ref mut items, .. } => {
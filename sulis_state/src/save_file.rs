@@ -14,18 +14,25 @@
 //  You should have received a copy of the GNU General Public License
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
+use std::cell::RefCell;
 use std::fs::{self, File};
-use std::io::{Error, Read};
+use std::io::{Error, Read, Write};
 use std::path::{Path, PathBuf};
 use std::time;
 
 use chrono::prelude::*;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 
+use crate::save_migration::{self, CURRENT_SAVE_VERSION};
 use crate::{GameState, SaveState};
-use sulis_core::resource::{read_single_resource_path, write_json_to_file};
-use sulis_core::util::invalid_data_error;
-use sulis_core::{config, serde_json, util};
-use sulis_module::Module;
+use sulis_core::config::{self, Config};
+use sulis_core::io::{InputRecorder, InputRecording, Thumbnail};
+use sulis_core::serde_json::{self, value::RawValue, Map, Value};
+use sulis_core::util::{self, invalid_data_error, ActiveResources};
+use sulis_module::modification::get_available_modifications;
+use sulis_module::{Module, Time};
 
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -34,24 +41,253 @@ pub struct SaveFile {
     state: SaveState,
 }
 
+/// The first bytes of every compressed save file.  The remainder of the
+/// file is the zlib compressed JSON of the save.
+const COMPRESSED_MAGIC: &[u8] = b"SULISSAV";
+
+const JSON_EXTENSION: &str = "json";
+const COMPRESSED_EXTENSION: &str = "sav";
+
+/// The extension of the file each save is written to before it is renamed
+/// over the previous save
+const TEMP_EXTENSION: &str = "tmp";
+
+/// The events that may cause an autosave, depending on the `SaveConfig`.
+/// In ironman mode, every trigger causes an autosave.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AutosaveTrigger {
+    AreaTransition,
+    Rest,
+
+    /// Only autosaves in ironman mode
+    CombatEnd,
+}
+
+#[derive(Default)]
+struct AutosaveTimer {
+    requested: bool,
+    elapsed_millis: u64,
+
+    /// The file name, without extension, of the single save slot for the
+    /// current ironman game, once it has been saved
+    ironman_save: Option<String>,
+
+    /// Set once the current game can no longer be saved, such as after
+    /// its ironman save was deleted
+    disabled: bool,
+}
+
+thread_local! {
+    static AUTOSAVE: RefCell<AutosaveTimer> = RefCell::new(AutosaveTimer::default());
+}
+
+/// The save file with its state kept as the exact JSON text that was
+/// written, so that the checksum in the meta data can be verified
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSaveFile<M, S> {
+    meta: M,
+    state: S,
+}
+
 impl SaveFile {
-    fn from_json(data: &str) -> Result<Self, Error> {
-        let resource: Result<SaveFile, serde_json::Error> = serde_json::from_str(data);
+    /// Creates a save file of the current game, with the same meta data and
+    /// state that is written by the game when saving
+    pub fn create() -> SaveFile {
+        SaveFile {
+            meta: create_meta_data(Utc::now().format("%c").to_string()),
+            state: SaveState::create(),
+        }
+    }
+
+    pub fn into_state(self) -> SaveState {
+        self.state
+    }
+
+    /// Parses the save file from the contents of either a JSON or a
+    /// compressed save file
+    fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        if data.starts_with(COMPRESSED_MAGIC) {
+            let mut decoder = ZlibDecoder::new(&data[COMPRESSED_MAGIC.len()..]);
+            let mut json = String::new();
+            if let Err(e) = decoder.read_to_string(&mut json) {
+                return invalid_data_error(&format!("Save file is truncated or corrupt: {}", e));
+            }
+            SaveFile::from_json(&json)
+        } else {
+            match std::str::from_utf8(data) {
+                Ok(json) => SaveFile::from_json(json),
+                Err(e) => invalid_data_error(&format!("Save file is not valid JSON: {}", e)),
+            }
+        }
+    }
+
+    /// Parses the save file from the specified JSON.  The state is checked
+    /// against the checksum in the meta data, if there is one, and the save
+    /// is then upgraded to the current save format version if needed.
+    pub fn from_json(data: &str) -> Result<Self, Error> {
+        let raw: RawSaveFile<Value, Box<RawValue>> = match serde_json::from_str(data) {
+            Ok(raw) => raw,
+            Err(e) => {
+                return invalid_data_error(&format!("Save file is truncated or corrupt: {}", e))
+            }
+        };
+
+        let state_json = raw.state.get();
+        if let Some(expected) = raw.meta.get("checksum").and_then(|c| c.as_u64()) {
+            let actual = checksum(state_json);
+            if u64::from(actual) != expected {
+                return invalid_data_error(&format!(
+                    "Save file is corrupt: checksum is {:08x} but should be {:08x}",
+                    actual, expected
+                ));
+            }
+        }
+
+        let state: Value = match serde_json::from_str(state_json) {
+            Ok(state) => state,
+            Err(e) => return invalid_data_error(&format!("{}", e)),
+        };
+
+        let mut value = Map::new();
+        value.insert("meta".to_string(), raw.meta);
+        value.insert("state".to_string(), state);
+        let mut value = Value::Object(value);
+
+        save_migration::migrate(&mut value)?;
+
+        let resource: Result<SaveFile, serde_json::Error> = serde_json::from_value(value);
 
         match resource {
             Ok(resource) => Ok(resource),
             Err(error) => invalid_data_error(&format!("{}", error)),
         }
     }
+
+    /// Returns the JSON of this save file, as it is written to disk.  The
+    /// checksum in the meta data is updated to match the state.
+    pub fn to_json(&mut self) -> Result<String, Error> {
+        let state = match serde_json::to_string(&self.state) {
+            Ok(state) => state,
+            Err(e) => return invalid_data_error(&format!("{}", e)),
+        };
+        self.meta.checksum = Some(checksum(&state));
+
+        let state = match RawValue::from_string(state) {
+            Ok(state) => state,
+            Err(e) => return invalid_data_error(&format!("{}", e)),
+        };
+
+        let raw = RawSaveFile {
+            meta: &self.meta,
+            state: &state,
+        };
+
+        match serde_json::to_string(&raw) {
+            Ok(json) => Ok(json),
+            Err(e) => invalid_data_error(&format!("{}", e)),
+        }
+    }
+
+    /// Writes this save file to the specified path, as compressed binary
+    /// if `compress` is true or JSON otherwise.  The save is written to a
+    /// temporary file and then renamed over any existing file at the path,
+    /// so a failed write never leaves a truncated save behind.
+    fn write(&mut self, path: &Path, compress: bool) -> Result<(), Error> {
+        let json = self.to_json()?;
+
+        let temp_path = path.with_extension(TEMP_EXTENSION);
+        if let Err(e) = write_synced(&temp_path, &json, compress) {
+            if temp_path.is_file() {
+                let _ = fs::remove_file(&temp_path);
+            }
+            return Err(e);
+        }
+
+        fs::rename(&temp_path, path)
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+/// Writes the save to the file at `path`, and waits until it is on disk
+fn write_synced(path: &Path, json: &str, compress: bool) -> Result<(), Error> {
+    let mut file = File::create(path)?;
+    if compress {
+        file.write_all(COMPRESSED_MAGIC)?;
+        let mut encoder = ZlibEncoder::new(file, Compression::default());
+        encoder.write_all(json.as_bytes())?;
+        file = encoder.finish()?;
+    } else {
+        file.write_all(json.as_bytes())?;
+    }
+    file.sync_all()
+}
+
+fn checksum(data: &str) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(data.as_bytes());
+    hasher.finalize()
+}
+
+fn is_save_file(path: &Path) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+
+    match path.extension() {
+        None => false,
+        Some(ext) => ext == JSON_EXTENSION || ext == COMPRESSED_EXTENSION,
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct SaveFileMetaData {
+    /// The save format version, see `save_migration`.  This is always
+    /// `CURRENT_SAVE_VERSION` once the save file has been read.
+    #[serde(default)]
+    pub version: u32,
+
+    /// The CRC-32 of the save's state JSON, used to detect corrupt saves.
+    /// Saves written before checksums were added have none.
+    #[serde(default)]
+    pub checksum: Option<u32>,
+
+    /// Whether this save was made automatically, in one of the rotating
+    /// autosave slots
+    #[serde(default)]
+    pub autosave: bool,
+
+    /// Whether this is the single save slot of an ironman game
+    #[serde(default)]
+    pub ironman: bool,
+
     pub player_name: String,
     pub datetime: String,
     pub current_area_name: String,
 
+    #[serde(default)]
+    pub campaign_name: String,
+
+    /// The directory of the module the save was made with.  Saves made
+    /// before the module and mods were recorded have none.
+    #[serde(default)]
+    pub module_dir: Option<String>,
+
+    /// The mods that were active when the save was made, in load order
+    #[serde(default)]
+    pub mods: Vec<ModMetaData>,
+
+    #[serde(default)]
+    pub party: Vec<PartyMemberMetaData>,
+
+    /// The in game time when the save was made
+    #[serde(default)]
+    pub time: Option<Time>,
+
+    /// The total time spent playing this game
+    #[serde(default)]
+    pub play_time_millis: u64,
+
     #[serde(skip)]
     path: PathBuf,
 
@@ -59,6 +295,109 @@ pub struct SaveFileMetaData {
     pub error: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ModMetaData {
+    pub id: String,
+    pub name: String,
+    pub dir: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PartyMemberMetaData {
+    pub name: String,
+    pub level: u32,
+
+    /// The name of each of the member's classes, with the levels in it
+    pub classes: Vec<(String, u32)>,
+}
+
+impl SaveFileMetaData {
+    /// The path of the thumbnail image written alongside the save, if any
+    pub fn thumbnail_path(&self) -> Option<PathBuf> {
+        let path = thumbnail_path(&self.path);
+        if path.is_file() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Compares the mods this save was made with against the currently
+    /// active mods, returning the differences if they do not match.  Saves
+    /// that did not record their mods are assumed to match.
+    pub fn mod_mismatch(&self) -> Option<ModMismatch> {
+        self.module_dir.as_ref()?;
+
+        let active = active_mods();
+        let saved_ids: Vec<&str> = self.mods.iter().map(|m| m.id.as_str()).collect();
+        let active_ids: Vec<&str> = active.iter().map(|m| m.id.as_str()).collect();
+        if saved_ids == active_ids {
+            return None;
+        }
+
+        let available = get_available_modifications();
+        let unavailable = self
+            .mods
+            .iter()
+            .filter(|m| !available.iter().any(|info| info.id == m.id))
+            .cloned()
+            .collect();
+
+        Some(ModMismatch {
+            saved: self.mods.clone(),
+            active,
+            unavailable,
+        })
+    }
+
+    /// The `ActiveResources` for the module and mods this save was made
+    /// with.  Mods are looked up by ID among the installed mods, and any that
+    /// are not installed are left out.
+    pub fn active_resources(&self) -> ActiveResources {
+        let available = get_available_modifications();
+        let mods = self
+            .mods
+            .iter()
+            .filter_map(|m| available.iter().find(|info| info.id == m.id))
+            .map(|info| info.dir.to_string())
+            .collect();
+
+        let campaign = match self.module_dir {
+            Some(ref dir) if Path::new(dir).is_dir() => Some(dir.to_string()),
+            _ => ActiveResources::read().campaign,
+        };
+
+        ActiveResources { campaign, mods }
+    }
+}
+
+/// The differences between the mods a save was made with and the currently
+/// active mods
+#[derive(Debug, Clone)]
+pub struct ModMismatch {
+    /// The mods the save was made with, in load order
+    pub saved: Vec<ModMetaData>,
+
+    /// The currently active mods, in load order
+    pub active: Vec<ModMetaData>,
+
+    /// The mods the save was made with that are not installed
+    pub unavailable: Vec<ModMetaData>,
+}
+
+fn active_mods() -> Vec<ModMetaData> {
+    Module::active_mods()
+        .into_iter()
+        .map(|info| ModMetaData {
+            id: info.id,
+            name: info.name,
+            dir: info.dir,
+        })
+        .collect()
+}
+
 fn get_save_dir() -> PathBuf {
     let mut path = config::USER_DIR.clone();
     path.push("save");
@@ -68,22 +407,253 @@ fn get_save_dir() -> PathBuf {
 
 pub fn delete_save(save_file: &SaveFileMetaData) -> Result<(), Error> {
     let path = save_file.path.as_path();
+    remove_sidecar_files(path)?;
     fs::remove_file(path)
 }
 
+fn replay_path(save_path: &Path) -> PathBuf {
+    save_path.with_extension("replay")
+}
+
+fn thumbnail_path(save_path: &Path) -> PathBuf {
+    save_path.with_extension("png")
+}
+
+/// Removes the replay and thumbnail files for the specified save path,
+/// if they exist
+fn remove_sidecar_files(save_path: &Path) -> Result<(), Error> {
+    for path in [replay_path(save_path), thumbnail_path(save_path)].iter() {
+        if path.is_file() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
 pub fn load_state(save_file: &SaveFileMetaData) -> Result<SaveState, Error> {
     let path = save_file.path.as_path();
-    let save_file: SaveFile = read_single_resource_path(path)?;
+    let save_file = read_save_file(path)?;
+
+    let ironman_save = if save_file.state.ironman {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    AUTOSAVE.with(|a| {
+        *a.borrow_mut() = AutosaveTimer {
+            ironman_save,
+            ..Default::default()
+        }
+    });
+
+    if Config::debug().replay_input {
+        let replay_path = replay_path(path);
+        if replay_path.is_file() {
+            let recording = InputRecording::read(&replay_path)?;
+            InputRecorder::start_replay(recording);
+        }
+    }
 
     Ok(save_file.state)
 }
 
+/// Creates a save of the current game state, and then begins recording
+/// all input to a replay file alongside it.  Loading the save with
+/// `replay_input` enabled will then reproduce the recorded session.
+pub fn start_input_recording() -> Result<(), Error> {
+    let path = write_save()?;
+    InputRecorder::start_recording(replay_path(&path));
+    Ok(())
+}
+
+/// Stops any current input recording, writing out the replay file
+pub fn finish_input_recording() -> Result<(), Error> {
+    InputRecorder::stop_recording()
+}
+
 pub fn create_save() -> Result<(), Error> {
+    write_save().map(|_| ())
+}
+
+/// Resets the autosave timer and ironman save slot for a newly started game
+pub(crate) fn reset_autosave() {
+    AUTOSAVE.with(|a| *a.borrow_mut() = AutosaveTimer::default());
+}
+
+/// Requests an autosave for the specified trigger, if autosaving is enabled
+/// for it.  The save is made by the next call to `update_autosave`.
+pub fn request_autosave(trigger: AutosaveTrigger) {
+    let enabled = if GameState::is_ironman() {
+        true
+    } else {
+        let config = Config::save_config();
+        let enabled = match trigger {
+            AutosaveTrigger::AreaTransition => config.autosave_on_transition,
+            AutosaveTrigger::Rest => config.autosave_on_rest,
+            AutosaveTrigger::CombatEnd => false,
+        };
+        enabled && config.autosave_slots > 0
+    };
+
+    if enabled {
+        debug!("Autosave requested for {:?}", trigger);
+        AUTOSAVE.with(|a| a.borrow_mut().requested = true);
+    }
+}
+
+/// Advances the autosave timer by the specified number of millis of play.
+/// If an autosave has been requested or the autosave interval has passed,
+/// and combat is not active, an autosave is made and its result returned.
+/// In ironman mode, the autosave overwrites the game's single save slot.
+pub fn update_autosave(millis: u32) -> Option<Result<(), Error>> {
+    let config = Config::save_config();
+    let ironman = GameState::is_ironman();
+    if config.autosave_slots == 0 && !ironman {
+        return None;
+    }
+
+    let interval = u64::from(config.autosave_interval_minutes) * 60_000;
+    let due = AUTOSAVE.with(|a| {
+        let mut a = a.borrow_mut();
+        a.elapsed_millis += u64::from(millis);
+        !a.disabled && (a.requested || (interval > 0 && a.elapsed_millis >= interval))
+    });
+
+    if !due || GameState::is_combat_active() {
+        return None;
+    }
+
+    AUTOSAVE.with(|a| {
+        let mut a = a.borrow_mut();
+        a.requested = false;
+        a.elapsed_millis = 0;
+    });
+
+    if ironman {
+        Some(write_ironman_save())
+    } else {
+        Some(create_autosave(config.autosave_slots))
+    }
+}
+
+fn create_autosave(slots: u32) -> Result<(), Error> {
+    let dir = get_save_dir();
+    let slot = next_autosave_slot(&dir, slots);
+    let name = format!("autosave_{}", slot);
+
+    remove_save_files(&dir, &name)?;
+
+    write_save_file(&name, true).map(|_| ())
+}
+
+/// Writes the current ironman game to its save slot, replacing the
+/// previous save in it
+fn write_ironman_save() -> Result<(), Error> {
+    let name = AUTOSAVE.with(|a| {
+        let mut a = a.borrow_mut();
+        a.ironman_save
+            .get_or_insert_with(|| {
+                let utc = Utc::now();
+                format!("ironman_{}", utc.format("%Y%m%d-%H%M%S%.3f"))
+            })
+            .to_string()
+    });
+
+    // the new save replaces a previous save with the same extension when it
+    // is complete, and a previous save with the other extension is only
+    // removed after that, so a failed write does not lose it
+    let path = write_save_file(&name, false)?;
+    for other in autosave_paths(&get_save_dir(), &name).iter() {
+        if *other != path && other.is_file() {
+            fs::remove_file(other)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Deletes the save of the current ironman game, if it has one, and
+/// disables any further autosaves of it.  This is done once the party has
+/// been defeated.
+pub fn delete_ironman_save() -> Result<(), Error> {
+    let name = AUTOSAVE.with(|a| {
+        let mut a = a.borrow_mut();
+        a.requested = false;
+        a.disabled = true;
+        a.ironman_save.take()
+    });
+
+    match name {
+        None => Ok(()),
+        Some(name) => {
+            info!("Deleting ironman save '{}'", name);
+            remove_save_files(&get_save_dir(), &name)
+        }
+    }
+}
+
+/// Removes the save with the specified file name, without extension, along
+/// with its replay and thumbnail
+fn remove_save_files(dir: &Path, name: &str) -> Result<(), Error> {
+    for path in autosave_paths(dir, name).iter() {
+        remove_sidecar_files(path)?;
+        if path.is_file() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn autosave_paths(dir: &Path, name: &str) -> [PathBuf; 2] {
+    let path = dir.join(name);
+    [
+        path.with_extension(JSON_EXTENSION),
+        path.with_extension(COMPRESSED_EXTENSION),
+    ]
+}
+
+/// Returns the first unused autosave slot, or the slot with the oldest
+/// autosave if all are in use
+fn next_autosave_slot(dir: &Path, slots: u32) -> u32 {
+    let mut oldest: Option<(u32, time::SystemTime)> = None;
+    for slot in 0..slots {
+        let name = format!("autosave_{}", slot);
+        let modified = autosave_paths(dir, &name)
+            .iter()
+            .filter_map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+            .max();
+
+        let modified = match modified {
+            None => return slot,
+            Some(modified) => modified,
+        };
+
+        match oldest {
+            Some((_, time)) if time <= modified => (),
+            _ => oldest = Some((slot, modified)),
+        }
+    }
+
+    oldest.map_or(0, |(slot, _)| slot)
+}
+
+fn write_save() -> Result<PathBuf, Error> {
+    let utc = Utc::now();
+    let name = format!("save_{}", utc.format("%Y%m%d-%H%M%S%.3f"));
+    write_save_file(&name, false)
+}
+
+fn write_save_file(name: &str, autosave: bool) -> Result<PathBuf, Error> {
     let start_time = time::Instant::now();
     info!("Start save");
 
-    let utc = Utc::now();
-    let filename = format!("save_{}.json", utc.format("%Y%m%d-%H%M%S%.3f"));
+    let compress = Config::save_config().compress;
+    let extension = if compress {
+        COMPRESSED_EXTENSION
+    } else {
+        JSON_EXTENSION
+    };
 
     let mut path = get_save_dir();
     if !path.is_dir() {
@@ -91,9 +661,11 @@ pub fn create_save() -> Result<(), Error> {
         fs::create_dir_all(path.clone())?;
     }
 
-    path.push(filename);
+    path.push(name);
+    path.set_extension(extension);
 
-    let meta = create_meta_data(utc.format("%c").to_string());
+    let mut meta = create_meta_data(Utc::now().format("%c").to_string());
+    meta.autosave = autosave;
 
     info!(
         "  Filename and meta data creation complete in {} secs",
@@ -102,21 +674,24 @@ pub fn create_save() -> Result<(), Error> {
 
     let state = SaveState::create();
 
-    let save = SaveFile { meta, state };
+    let mut save = SaveFile { meta, state };
 
     info!(
         "  Save data created in {} secs",
         util::format_elapsed_secs(start_time.elapsed())
     );
 
-    let result = write_json_to_file(path.as_path(), &save);
+    let result = save.write(&path, compress);
+    if result.is_ok() {
+        Thumbnail::request(thumbnail_path(&path));
+    }
 
     info!(
         "  Save to disk complete in {} secs",
         util::format_elapsed_secs(start_time.elapsed())
     );
 
-    result
+    result.map(|_| path)
 }
 
 fn create_meta_data(datetime: String) -> SaveFileMetaData {
@@ -125,10 +700,38 @@ fn create_meta_data(datetime: String) -> SaveFileMetaData {
     let player = GameState::player();
     let player = player.borrow();
 
+    let party = GameState::party()
+        .iter()
+        .map(|member| {
+            let actor = &member.borrow().actor.actor;
+            PartyMemberMetaData {
+                name: actor.name.to_string(),
+                level: actor.total_level,
+                classes: actor
+                    .levels
+                    .iter()
+                    .map(|(class, level)| (class.name.to_string(), *level))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    let time = GameState::turn_manager().borrow().current_time();
+
     SaveFileMetaData {
+        version: CURRENT_SAVE_VERSION,
+        checksum: None,
+        autosave: false,
+        ironman: GameState::is_ironman(),
         player_name: player.actor.actor.name.to_string(),
         datetime,
         current_area_name: cur_area.area.area.name.to_string(),
+        campaign_name: Module::campaign().name.to_string(),
+        module_dir: Module::module_dir(),
+        mods: active_mods(),
+        party,
+        time: Some(time),
+        play_time_millis: GameState::play_time_millis(),
         path: Default::default(),
         error: None,
     }
@@ -151,21 +754,9 @@ pub fn has_available_save_files() -> bool {
             Ok(entry) => entry,
         };
 
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
+        if is_save_file(&entry.path()) {
+            return true;
         }
-
-        let extension = match path.extension() {
-            None => continue,
-            Some(ext) => ext.to_string_lossy(),
-        };
-
-        if extension != "json" {
-            continue;
-        }
-
-        return true;
     }
 
     false
@@ -174,10 +765,10 @@ pub fn has_available_save_files() -> bool {
 fn read_save_file(path: &Path) -> Result<SaveFile, Error> {
     let mut file = File::open(path)?;
 
-    let mut file_data = String::new();
-    file.read_to_string(&mut file_data)?;
+    let mut file_data = Vec::new();
+    file.read_to_end(&mut file_data)?;
 
-    SaveFile::from_json(&file_data)
+    SaveFile::from_bytes(&file_data)
 }
 
 fn create_error_meta(path: PathBuf, error: Error) -> SaveFileMetaData {
@@ -208,6 +799,7 @@ fn create_error_meta(path: PathBuf, error: Error) -> SaveFileMetaData {
         current_area_name: "Unknown Area".to_string(),
         path,
         error: Some(error.to_string()),
+        ..Default::default()
     }
 }
 
@@ -228,16 +820,7 @@ pub fn get_available_save_files() -> Result<Vec<SaveFileMetaData>, Error> {
         let entry = entry?;
 
         let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-
-        let extension = match path.extension() {
-            None => continue,
-            Some(ext) => ext.to_string_lossy(),
-        };
-
-        if extension != "json" {
+        if !is_save_file(&path) {
             continue;
         }
 
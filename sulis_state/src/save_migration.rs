@@ -0,0 +1,108 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Upgrades save files written by older versions of the engine.  Save files
+//! are parsed as untyped JSON first, then passed through each migration from
+//! their format version up to `CURRENT_SAVE_VERSION`, and only then
+//! deserialized into a `SaveFile`.
+//!
+//! When making a change to `SaveState` or any of the structures it contains
+//! that would prevent older saves from deserializing, increment
+//! `CURRENT_SAVE_VERSION` and add a function to `MIGRATIONS` converting the
+//! JSON from the previous version.
+
+use std::io::Error;
+
+use sulis_core::serde_json::{Map, Value};
+use sulis_core::util::invalid_data_error;
+
+/// The format version written into all new save files.  Saves without a
+/// version were written before versioning was added, and are version 0.
+pub const CURRENT_SAVE_VERSION: u32 = 1;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<(), Error>;
+
+/// The migration at index `i` converts a save from version `i` to version
+/// `i + 1`.  There must be exactly `CURRENT_SAVE_VERSION` entries.
+const MIGRATIONS: [Migration; CURRENT_SAVE_VERSION as usize] = [migrate_0_to_1];
+
+/// Reads the format version from the meta data of the specified save file JSON
+pub fn save_version(save: &Value) -> Result<u32, Error> {
+    let meta = match save.get("meta") {
+        None => return invalid_data_error("Save file has no meta data"),
+        Some(meta) => meta,
+    };
+
+    match meta.get("version") {
+        None => Ok(0),
+        Some(version) => match version.as_u64() {
+            Some(version) if version <= u64::from(u32::MAX) => Ok(version as u32),
+            _ => invalid_data_error(&format!("Invalid save file version '{}'", version)),
+        },
+    }
+}
+
+/// Upgrades the specified save file JSON in place to `CURRENT_SAVE_VERSION`.
+/// Returns an error if the save is from a newer version of the engine than
+/// this one, or if any migration fails.
+pub fn migrate(save: &mut Value) -> Result<(), Error> {
+    let version = save_version(save)?;
+
+    if version > CURRENT_SAVE_VERSION {
+        return invalid_data_error(&format!(
+            "This save was created by a newer version of Sulis (save format {}, \
+             but this version only supports up to {}).  Please update Sulis to load it.",
+            version, CURRENT_SAVE_VERSION
+        ));
+    }
+
+    let root = match save.as_object_mut() {
+        None => return invalid_data_error("Save file is not a JSON object"),
+        Some(root) => root,
+    };
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        info!(
+            "Migrating save file from version {} to {}",
+            index,
+            index + 1
+        );
+        if let Err(e) = migration(root) {
+            return invalid_data_error(&format!(
+                "Unable to upgrade save from version {}: {}",
+                index, e
+            ));
+        }
+        set_version(root, index as u32 + 1)?;
+    }
+
+    Ok(())
+}
+
+fn set_version(root: &mut Map<String, Value>, version: u32) -> Result<(), Error> {
+    match root.get_mut("meta").and_then(|meta| meta.as_object_mut()) {
+        None => invalid_data_error("Save file meta data is not a JSON object"),
+        Some(meta) => {
+            meta.insert("version".to_string(), Value::from(version));
+            Ok(())
+        }
+    }
+}
+
+/// Version 1 adds only the format version itself to the meta data.
+fn migrate_0_to_1(_root: &mut Map<String, Value>) -> Result<(), Error> {
+    Ok(())
+}
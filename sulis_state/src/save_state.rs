@@ -20,15 +20,15 @@ use std::io::Error;
 use std::rc::Rc;
 use std::u64;
 
-use sulis_core::util::{ExtInt, Point};
+use sulis_core::util::{ExtInt, Point, ReproducibleRandom};
 use sulis_module::{
-    actor::{ActorBuilder, RewardBuilder},
-    BonusList, ItemListEntrySaveState, ItemSaveState, QuickSlot, Slot,
+    actor::ActorBuilder, BonusList, ItemListEntrySaveState, ItemSaveState, PartyAIPreset,
+    QuickSlot, Schedule, Slot,
 };
 
 use crate::animation::AnimSaveState;
 use crate::area_state::TriggerState;
-use crate::script::CallbackData;
+use crate::script::{CallbackData, ScriptSubscription, ScriptSubscriptions};
 use crate::{
     effect, prop_state::Interactive, turn_manager::EncounterRef, ActorState, Effect, EntityState,
     Formation, GameState, Location, MerchantState, PStats, PropState, QuestState, WorldMapState,
@@ -54,6 +54,21 @@ pub struct SaveState {
 
     #[serde(default)]
     pub(crate) total_elapsed_millis: usize,
+
+    #[serde(default)]
+    pub(crate) random: Option<ReproducibleRandom>,
+
+    #[serde(default)]
+    pub(crate) play_time_millis: u64,
+
+    #[serde(default)]
+    pub(crate) ironman: bool,
+
+    #[serde(default)]
+    pub(crate) difficulty: Option<String>,
+
+    #[serde(default)]
+    pub(crate) script_subscriptions: Vec<ScriptSubscription>,
 }
 
 fn default_zoom() -> f32 {
@@ -116,6 +131,11 @@ impl SaveState {
             world_map: GameState::world_map(),
             quests: quest_state,
             total_elapsed_millis,
+            random: Some(GameState::save_random()),
+            play_time_millis: GameState::play_time_millis(),
+            ironman: GameState::is_ironman(),
+            difficulty: Some(GameState::difficulty().id.to_string()),
+            script_subscriptions: ScriptSubscriptions::subscriptions(),
         }
     }
 
@@ -431,6 +451,18 @@ pub struct EntitySaveState {
 
     #[serde(default)]
     pub(crate) collapsed_groups: Vec<String>,
+
+    #[serde(default)]
+    pub(crate) party_ai: Option<PartyAIPreset>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) schedule: Option<Schedule>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) schedule_home: Option<Point>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) schedule_random: Option<ReproducibleRandom>,
 }
 
 impl EntitySaveState {
@@ -438,54 +470,7 @@ impl EntitySaveState {
         let entity = entity.borrow();
 
         let actor_base = if entity.is_party_member() {
-            let actor = &entity.actor.actor;
-
-            let mut levels = HashMap::new();
-            for (ref class, level) in actor.levels.iter() {
-                levels.insert(class.id.to_string(), *level);
-            }
-
-            let reward = match actor.reward {
-                None => None,
-                Some(ref reward) => Some(RewardBuilder {
-                    xp: reward.xp,
-                    loot: reward.loot.as_ref().map(|l| l.id.to_string()),
-                    loot_chance: Some(reward.loot_chance),
-                }),
-            };
-
-            let mut abilities: Vec<String> = Vec::new();
-            for owned_ability in actor.abilities.iter() {
-                for _ in 0..(owned_ability.level + 1) {
-                    abilities.push(owned_ability.ability.id.to_string());
-                }
-            }
-
-            let ai = match &actor.ai {
-                None => None,
-                Some(ai) => Some(ai.id.to_string()),
-            };
-
-            Some(ActorBuilder {
-                id: actor.id.to_string(),
-                name: actor.name.to_string(),
-                race: actor.race.id.to_string(),
-                sex: Some(actor.sex),
-                portrait: actor.portrait.as_ref().map(|p| p.id().to_string()),
-                attributes: actor.attributes,
-                conversation: actor.conversation.as_ref().map(|c| c.id.to_string()),
-                faction: Some(actor.faction()),
-                images: actor.builder_images.clone(),
-                hue: actor.hue,
-                hair_color: actor.hair_color,
-                skin_color: actor.skin_color,
-                inventory: actor.inventory.clone(),
-                levels,
-                xp: Some(actor.xp),
-                reward,
-                abilities,
-                ai,
-            })
+            Some(entity.actor.actor.to_builder())
         } else {
             None
         };
@@ -507,6 +492,10 @@ impl EntitySaveState {
             show_portrait: entity.show_portrait(),
             actor_base,
             collapsed_groups: entity.collapsed_groups(),
+            party_ai: entity.party_ai(),
+            schedule: entity.schedule().map(|schedule| (**schedule).clone()),
+            schedule_home: entity.schedule.as_ref().map(|state| state.home()),
+            schedule_random: entity.schedule.as_ref().map(|state| state.random().clone()),
         }
     }
 }
@@ -18,7 +18,7 @@
 //! objects.  The documentation for each struct describes the available functions on each
 //! object when interacting with them within a lua script.
 //!
-//! There are currently four kinds of scripts:
+//! There are currently five kinds of scripts:
 //!
 //! 1. AI Scripts:  These are attached to a given actor in their resource definition under `ai`.
 //!    Whenever the parent entity is active, the `ai_action(parent, state)` method is called.
@@ -31,6 +31,9 @@
 //!    targeter.
 //! 4. Item Scripts: Similar to ability scripts, but called when using an item.  The entry point is
 //!    `on_activate(parent, item)`.
+//! 5. Event Scripts: Named script functions subscribed to a global gameplay event with
+//!    `game:subscribe(event, script_id, func)`.  The function is called with a table describing
+//!    each event, such as any entity dying or the party entering an area.
 //!
 //! Since standard Lua methods for referencing other script files will not work, Sulis includes
 //! a simple facility to include the contents of a script into another script.  This is done
@@ -60,6 +63,9 @@ pub use self::script_effect::{
 mod script_entity;
 pub use self::script_entity::ScriptEntity;
 
+pub mod script_event;
+pub use self::script_event::{ScriptGameEvent, ScriptSubscription, ScriptSubscriptions};
+
 mod script_entity_set;
 pub use self::script_entity_set::ScriptEntitySet;
 
@@ -93,6 +99,12 @@ pub use self::script_subpos_animation::ScriptSubposAnimation;
 pub mod targeter;
 pub use self::targeter::TargeterData;
 
+pub mod script_api;
+pub use self::script_api::ScriptApi;
+
+pub mod script_lint;
+pub use self::script_lint::ScriptLint;
+
 use std;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
@@ -121,6 +133,23 @@ impl Script {
         }
     }
 
+    /// Calls the utility AI override `func`, returning the AI state if the
+    /// script chose to override the native AI
+    pub fn ai_override(
+        parent: &Rc<RefCell<EntityState>>,
+        script: &str,
+        func: &str,
+        actions: Vec<ai::UtilityAction>,
+    ) -> Option<ai::State> {
+        match script_cache::ai_override_script(parent, script, func, actions) {
+            Err(e) => {
+                warn!("Error in lua AI override script '{}': {}", func, e);
+                None
+            }
+            Ok(val) => val,
+        }
+    }
+
     pub fn entity(parent: &Rc<RefCell<EntityState>>, targets: ScriptEntitySet, func: &str) {
         let t: Option<usize> = None;
         if let Err(e) = script_cache::entity_script(parent, targets, t, func) {
@@ -338,6 +367,11 @@ impl ScriptState {
                     warn!("{}", e);
                 }
             }
+
+            if let Err(e) = replace_math_random(lua) {
+                warn!("Error replacing Lua random functions");
+                warn!("{}", e);
+            }
         });
 
         let instructions = Arc::new(Mutex::new(InstructionState {
@@ -409,6 +443,13 @@ impl ScriptState {
             .context(|lua| lua.load(&script).set_name(&id)?.exec())
     }
 
+    /// Returns true if this script defines a global function with the
+    /// specified name
+    pub fn has_func(&self, function: &str) -> bool {
+        self.lua
+            .context(|lua| lua.globals().get::<_, Function>(function).is_ok())
+    }
+
     pub(in crate::script) fn exec_func<Args, Ret>(
         &self,
         function: &str,
@@ -480,6 +521,48 @@ fn get_targeter() -> Result<Rc<RefCell<AreaTargeter>>> {
     }
 }
 
+/// Replaces `math.random` and `math.randomseed` so that scripts draw from
+/// the gameplay random generator, keeping script rolls reproducible.  The
+/// Lua 5.3 argument conventions for `math.random` are preserved.
+fn replace_math_random(lua: rlua::Context) -> Result<()> {
+    let math: rlua::Table = lua.globals().get("math")?;
+
+    let random = lua.create_function(|_, (min, max): (Option<i64>, Option<i64>)| {
+        let (min, max) = match (min, max) {
+            (None, _) => {
+                let value = GameState::with_random(|rand| rand.gen(0.0, 1.0));
+                return Ok(rlua::Value::Number(value));
+            }
+            (Some(max), None) => (1, max),
+            (Some(min), Some(max)) => (min, max),
+        };
+
+        if min > max {
+            return Err(rlua::Error::RuntimeError(
+                "bad argument to 'random' (interval is empty)".to_string(),
+            ));
+        }
+
+        let end = match max.checked_add(1) {
+            None => {
+                return Err(rlua::Error::RuntimeError(
+                    "bad argument to 'random' (interval too large)".to_string(),
+                ))
+            }
+            Some(end) => end,
+        };
+
+        let value = GameState::with_random(|rand| rand.gen(min, end));
+        Ok(rlua::Value::Integer(value))
+    })?;
+    math.set("random", random)?;
+
+    let random_seed = lua.create_function(|_, _: rlua::MultiValue| Ok(()))?;
+    math.set("randomseed", random_seed)?;
+
+    Ok(())
+}
+
 fn get_rlua_std_lib() -> rlua::StdLib {
     use rlua::StdLib;
 
@@ -21,6 +21,8 @@ use std::rc::Rc;
 
 use rlua::{self, Context, UserData, UserDataMethods};
 
+use crate::combat_log::{CombatLog, CombatLogKind};
+use crate::event_bus::{EventBus, GameEvent};
 use crate::script::{CallbackData, ScriptEntity};
 use crate::{area_feedback_text::ColorKind, EntityState, GameState};
 use sulis_module::{
@@ -405,6 +407,14 @@ fn activate(
     let mut text = area.borrow_mut().create_feedback_text(&entity.borrow());
     text.add_entry(ability.name.to_string(), ColorKind::Info);
     area.borrow_mut().add_feedback_text(text);
+    CombatLog::push(CombatLogKind::AbilityUsed {
+        entity: entity.borrow().actor.actor.name.to_string(),
+        ability: ability.name.to_string(),
+    });
+    EventBus::publish(GameEvent::AbilityActivated {
+        entity: entity.borrow().index(),
+        ability: ability.id.to_string(),
+    });
     entity
         .borrow_mut()
         .actor
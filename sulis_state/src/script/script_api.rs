@@ -0,0 +1,568 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! A description of the Lua script API, built from the `UserData` bindings
+//! of each object passed to scripts.  The method names and argument and
+//! return types come directly from the bindings, while the documentation is
+//! taken from the `# \`method()\`` sections of each struct's doc comment,
+//! so the description always matches the build.
+
+use std::any::type_name;
+use std::collections::HashSet;
+
+use rlua::{Context, FromLuaMulti, MetaMethod, ToLuaMulti, UserData, UserDataMethods};
+
+use crate::script::script_callback::DamageEntry;
+use crate::script::*;
+
+/// A single method callable from Lua
+#[derive(Serialize, Debug, Clone)]
+pub struct ApiMethod {
+    pub name: String,
+
+    /// The Lua type of each argument, as determined by the binding
+    pub args: Vec<String>,
+
+    /// The Lua type of each return value, as determined by the binding
+    pub returns: Vec<String>,
+
+    /// The signature as documented, including argument names, or a signature
+    /// built from the argument and return types if undocumented
+    pub signature: String,
+    pub doc: String,
+}
+
+/// An object that is passed to Lua scripts, and its methods
+#[derive(Serialize, Debug, Clone)]
+pub struct ApiType {
+    pub name: String,
+    pub doc: String,
+    pub methods: Vec<ApiMethod>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ScriptApi {
+    pub version: String,
+    pub types: Vec<ApiType>,
+}
+
+impl ScriptApi {
+    pub fn new() -> ScriptApi {
+        let mut api = ScriptApi {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            types: Vec::new(),
+        };
+
+        api.add::<ScriptInterface>(include_str!("script_interface.rs"));
+        api.add::<ScriptEntity>(include_str!("script_entity.rs"));
+        api.add::<ScriptEntitySet>(include_str!("script_entity_set.rs"));
+        api.add::<ScriptAbility>(include_str!("script_ability.rs"));
+        api.add::<ScriptAbilitySet>(include_str!("script_ability.rs"));
+        api.add::<ScriptActiveSurface>(include_str!("script_effect.rs"));
+        api.add::<ScriptAppliedEffect>(include_str!("script_effect.rs"));
+        api.add::<ScriptEffect>(include_str!("script_effect.rs"));
+        api.add::<ScriptMenuSelection>(include_str!("script_effect.rs"));
+        api.add::<ScriptInventory>(include_str!("script_inventory.rs"));
+        api.add::<ScriptStashItem>(include_str!("script_inventory.rs"));
+        api.add::<ScriptUsableItem>(include_str!("script_inventory.rs"));
+        api.add::<ScriptItem>(include_str!("script_item.rs"));
+        api.add::<ScriptMenu>(include_str!("script_menu.rs"));
+        api.add::<TargeterData>(include_str!("targeter.rs"));
+        api.add::<CallbackData>(include_str!("script_callback.rs"));
+        api.add::<ScriptHitKind>(include_str!("script_callback.rs"));
+        api.add::<DamageEntry>(include_str!("script_callback.rs"));
+        api.add::<ScriptColorAnimation>(include_str!("script_color_animation.rs"));
+        api.add::<ScriptImageLayerAnimation>(include_str!("script_image_layer_animation.rs"));
+        api.add::<ScriptParticleGenerator>(include_str!("script_particle_generator.rs"));
+        api.add::<ScriptScaleAnimation>(include_str!("script_scale_animation.rs"));
+        api.add::<ScriptSubposAnimation>(include_str!("script_subpos_animation.rs"));
+
+        api
+    }
+
+    fn add<T: UserData>(&mut self, source: &str) {
+        let name = simplify_type(type_name::<T>());
+        let (doc, sections) = struct_doc(source, &name);
+
+        let mut collector = MethodCollector::default();
+        T::add_methods(&mut collector);
+
+        let methods = collector
+            .methods
+            .into_iter()
+            .map(|(name, args, returns)| {
+                let args = lua_types(args);
+                let returns = lua_types(returns);
+                let (signature, doc) = match sections.iter().find(|(sig, _)| sig_name(sig) == name)
+                {
+                    Some((sig, doc)) => (sig.to_string(), doc.to_string()),
+                    None => (build_signature(&name, &args, &returns), String::new()),
+                };
+
+                ApiMethod {
+                    name,
+                    args,
+                    returns,
+                    signature,
+                    doc,
+                }
+            })
+            .collect();
+
+        self.types.push(ApiType { name, doc, methods });
+    }
+
+    /// The names of all methods on all types in the API
+    pub fn method_names(&self) -> HashSet<String> {
+        self.types
+            .iter()
+            .flat_map(|t| t.methods.iter().map(|m| m.name.to_string()))
+            .collect()
+    }
+
+    /// Creates a Markdown reference page for the API
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# Sulis Lua Script API\n\n");
+        out.push_str(&format!(
+            "Generated from the script bindings of Sulis {}.\n\n",
+            self.version
+        ));
+
+        for api_type in self.types.iter() {
+            out.push_str(&format!(
+                "- [{}](#{})\n",
+                api_type.name,
+                api_type.name.to_lowercase()
+            ));
+        }
+
+        for api_type in self.types.iter() {
+            out.push_str(&format!("\n## {}\n\n", api_type.name));
+            push_markdown_doc(&mut out, &api_type.doc);
+
+            for method in api_type.methods.iter() {
+                out.push_str(&format!("\n### `{}`\n\n", method.signature));
+                out.push_str(&format!(
+                    "Binding: `{}`\n\n",
+                    build_signature(&method.name, &method.args, &method.returns)
+                ));
+                push_markdown_doc(&mut out, &method.doc);
+            }
+        }
+
+        out
+    }
+
+    /// Creates a standalone HTML reference page for the API
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str("<title>Sulis Lua Script API</title>\n</head>\n<body>\n");
+        out.push_str("<h1>Sulis Lua Script API</h1>\n");
+        out.push_str(&format!(
+            "<p>Generated from the script bindings of Sulis {}.</p>\n<ul>\n",
+            escape_html(&self.version)
+        ));
+
+        for api_type in self.types.iter() {
+            let name = escape_html(&api_type.name);
+            out.push_str(&format!("<li><a href=\"#{}\">{}</a></li>\n", name, name));
+        }
+        out.push_str("</ul>\n");
+
+        for api_type in self.types.iter() {
+            let name = escape_html(&api_type.name);
+            out.push_str(&format!("<h2 id=\"{}\">{}</h2>\n", name, name));
+            push_html_doc(&mut out, &api_type.doc);
+
+            for method in api_type.methods.iter() {
+                out.push_str(&format!(
+                    "<h3 id=\"{}.{}\"><code>{}</code></h3>\n",
+                    name,
+                    escape_html(&method.name),
+                    escape_html(&method.signature)
+                ));
+                let binding = build_signature(&method.name, &method.args, &method.returns);
+                out.push_str(&format!(
+                    "<p>Binding: <code>{}</code></p>\n",
+                    escape_html(&binding)
+                ));
+                push_html_doc(&mut out, &method.doc);
+            }
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+impl Default for ScriptApi {
+    fn default() -> Self {
+        ScriptApi::new()
+    }
+}
+
+/// Collects the name and argument and return types of each method
+/// registered by a `UserData` type
+#[derive(Default)]
+struct MethodCollector {
+    methods: Vec<(String, &'static str, &'static str)>,
+}
+
+impl MethodCollector {
+    fn add<S: ?Sized + AsRef<[u8]>, A, R>(&mut self, name: &S) {
+        let name = String::from_utf8_lossy(name.as_ref()).to_string();
+        self.methods
+            .push((name, type_name::<A>(), type_name::<R>()));
+    }
+}
+
+impl<'lua, T: UserData> UserDataMethods<'lua, T> for MethodCollector {
+    fn add_method<S, A, R, M>(&mut self, name: &S, _method: M)
+    where
+        S: ?Sized + AsRef<[u8]>,
+        A: FromLuaMulti<'lua>,
+        R: ToLuaMulti<'lua>,
+        M: 'static + Send + Fn(Context<'lua>, &T, A) -> Result<R>,
+    {
+        self.add::<S, A, R>(name);
+    }
+
+    fn add_method_mut<S, A, R, M>(&mut self, name: &S, _method: M)
+    where
+        S: ?Sized + AsRef<[u8]>,
+        A: FromLuaMulti<'lua>,
+        R: ToLuaMulti<'lua>,
+        M: 'static + Send + FnMut(Context<'lua>, &mut T, A) -> Result<R>,
+    {
+        self.add::<S, A, R>(name);
+    }
+
+    fn add_function<S, A, R, F>(&mut self, name: &S, _function: F)
+    where
+        S: ?Sized + AsRef<[u8]>,
+        A: FromLuaMulti<'lua>,
+        R: ToLuaMulti<'lua>,
+        F: 'static + Send + Fn(Context<'lua>, A) -> Result<R>,
+    {
+        self.add::<S, A, R>(name);
+    }
+
+    fn add_function_mut<S, A, R, F>(&mut self, name: &S, _function: F)
+    where
+        S: ?Sized + AsRef<[u8]>,
+        A: FromLuaMulti<'lua>,
+        R: ToLuaMulti<'lua>,
+        F: 'static + Send + FnMut(Context<'lua>, A) -> Result<R>,
+    {
+        self.add::<S, A, R>(name);
+    }
+
+    fn add_meta_method<A, R, M>(&mut self, _meta: MetaMethod, _method: M)
+    where
+        A: FromLuaMulti<'lua>,
+        R: ToLuaMulti<'lua>,
+        M: 'static + Send + Fn(Context<'lua>, &T, A) -> Result<R>,
+    {
+    }
+
+    fn add_meta_method_mut<A, R, M>(&mut self, _meta: MetaMethod, _method: M)
+    where
+        A: FromLuaMulti<'lua>,
+        R: ToLuaMulti<'lua>,
+        M: 'static + Send + FnMut(Context<'lua>, &mut T, A) -> Result<R>,
+    {
+    }
+
+    fn add_meta_function<A, R, F>(&mut self, _meta: MetaMethod, _function: F)
+    where
+        A: FromLuaMulti<'lua>,
+        R: ToLuaMulti<'lua>,
+        F: 'static + Send + Fn(Context<'lua>, A) -> Result<R>,
+    {
+    }
+
+    fn add_meta_function_mut<A, R, F>(&mut self, _meta: MetaMethod, _function: F)
+    where
+        A: FromLuaMulti<'lua>,
+        R: ToLuaMulti<'lua>,
+        F: 'static + Send + FnMut(Context<'lua>, A) -> Result<R>,
+    {
+    }
+}
+
+/// Removes the module paths and lifetimes from a Rust type name, so
+/// `core::option::Option<alloc::string::String>` becomes `Option<String>`
+fn simplify_type(name: &str) -> String {
+    let mut out = String::new();
+    let mut path = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            path.push(c);
+            continue;
+        }
+
+        out.push_str(path.rsplit("::").next().unwrap_or(""));
+        path.clear();
+        out.push(c);
+    }
+    out.push_str(path.rsplit("::").next().unwrap_or(""));
+
+    // lifetimes are always erased to '_ by type_name
+    out.replace("<'_>", "").replace("'_, ", "")
+}
+
+/// Converts a Rust argument or return type, which may be a tuple, into
+/// the list of Lua types it represents
+fn lua_types(rust_type: &str) -> Vec<String> {
+    let rust_type = simplify_type(rust_type);
+    let inner = if rust_type.starts_with('(') && rust_type.ends_with(')') {
+        &rust_type[1..rust_type.len() - 1]
+    } else {
+        &rust_type
+    };
+
+    split_top_level(inner)
+        .into_iter()
+        .map(|t| lua_type(&t))
+        .collect()
+}
+
+fn split_top_level(types: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut cur = String::new();
+    for c in types.chars() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(cur.trim().to_string());
+                cur.clear();
+                continue;
+            }
+            _ => (),
+        }
+        cur.push(c);
+    }
+    result.push(cur.trim().to_string());
+    result.retain(|t| !t.is_empty());
+    result
+}
+
+fn generic_arg<'a>(rust_type: &'a str, outer: &str) -> Option<&'a str> {
+    if rust_type.starts_with(outer) && rust_type.ends_with('>') {
+        let start = outer.len();
+        if rust_type[start..].starts_with('<') {
+            return Some(&rust_type[start + 1..rust_type.len() - 1]);
+        }
+    }
+    None
+}
+
+/// Converts a single simplified Rust type into the type names used in the
+/// script documentation
+fn lua_type(rust_type: &str) -> String {
+    let rust_type = rust_type.trim_start_matches('&');
+    if let Some(inner) = generic_arg(rust_type, "Option") {
+        return format!("{} (Optional)", lua_type(inner));
+    }
+
+    if let Some(inner) = generic_arg(rust_type, "Variadic") {
+        return format!("{}...", lua_type(inner));
+    }
+
+    if generic_arg(rust_type, "Vec").is_some() || generic_arg(rust_type, "HashMap").is_some() {
+        return "Table".to_string();
+    }
+
+    match rust_type {
+        "String" | "str" => "String",
+        "f32" | "f64" => "Float",
+        "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "usize" | "isize" => "Int",
+        "bool" => "Bool",
+        "Value" => "Any",
+        "State" => "AIState",
+        "()" => "Nil",
+        other => other,
+    }
+    .to_string()
+}
+
+fn build_signature(name: &str, args: &[String], returns: &[String]) -> String {
+    let mut sig = format!("{}({})", name, args.join(", "));
+    if !returns.is_empty() {
+        sig.push_str(&format!(" -> {}", returns.join(", ")));
+    }
+    sig
+}
+
+/// The method name of a documented signature such as `add_xp(amount: Int)`
+fn sig_name(sig: &str) -> &str {
+    sig.split(|c: char| c == '(' || c.is_whitespace())
+        .next()
+        .unwrap_or("")
+}
+
+/// Finds the doc comment of the struct with the specified name in the
+/// source, returning the struct description and each documented method
+/// signature along with its description
+fn struct_doc(source: &str, name: &str) -> (String, Vec<(String, String)>) {
+    let lines: Vec<&str> = source.lines().collect();
+    let decl = format!("struct {}", name);
+    let index = lines.iter().position(|line| {
+        let line = line.trim();
+        !line.starts_with("//")
+            && line.find(&decl).is_some_and(|pos| {
+                let rest = &line[pos + decl.len()..];
+                !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+            })
+    });
+
+    let index = match index {
+        None => return (String::new(), Vec::new()),
+        Some(index) => index,
+    };
+
+    let mut doc = Vec::new();
+    for line in lines[..index].iter().rev() {
+        let line = line.trim();
+        if line.starts_with("#[") {
+            continue;
+        } else if let Some(line) = line.strip_prefix("///") {
+            doc.push(line.strip_prefix(' ').unwrap_or(line));
+        } else {
+            break;
+        }
+    }
+    doc.reverse();
+
+    let mut description = Vec::new();
+    let mut sections: Vec<(String, String)> = Vec::new();
+    let mut sigs: Vec<String> = Vec::new();
+    let mut body: Vec<&str> = Vec::new();
+    for line in doc {
+        let sig = line
+            .strip_prefix("# `")
+            .and_then(|sig| sig.strip_suffix('`'));
+
+        match sig {
+            Some(sig) => {
+                // consecutive headings share the description that follows them
+                if !body.iter().all(|line| line.trim().is_empty()) {
+                    let text = join_doc(&body);
+                    sections.extend(sigs.drain(..).map(|sig| (sig, text.clone())));
+                    body.clear();
+                }
+                sigs.push(sig.to_string());
+            }
+            None if sigs.is_empty() => description.push(line),
+            None => body.push(line),
+        }
+    }
+
+    let text = join_doc(&body);
+    sections.extend(sigs.drain(..).map(|sig| (sig, text.clone())));
+
+    (join_doc(&description), sections)
+}
+
+fn join_doc(lines: &[&str]) -> String {
+    lines.join("\n").trim().to_string()
+}
+
+/// Appends a doc comment to Markdown output, moving its headings below the
+/// level of the method headings
+fn push_markdown_doc(out: &mut String, doc: &str) {
+    if doc.is_empty() {
+        return;
+    }
+
+    let mut in_code = false;
+    for line in doc.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        } else if !in_code && line.starts_with('#') {
+            out.push_str("###");
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push('\n');
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes the text and converts `inline code` spans
+fn inline_html(text: &str) -> String {
+    let mut out = String::new();
+    for (index, part) in escape_html(text).split('`').enumerate() {
+        if index % 2 == 1 {
+            out.push_str(&format!("<code>{}</code>", part));
+        } else {
+            out.push_str(part);
+        }
+    }
+    out
+}
+
+/// Appends a doc comment to HTML output, converting its headings, code
+/// blocks, and paragraphs
+fn push_html_doc(out: &mut String, doc: &str) {
+    let mut in_code = false;
+    let mut paragraph: Vec<&str> = Vec::new();
+
+    let flush = |out: &mut String, paragraph: &mut Vec<&str>| {
+        if !paragraph.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", inline_html(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+
+    for line in doc.lines() {
+        if line.trim_start().starts_with("```") {
+            flush(out, &mut paragraph);
+            out.push_str(if in_code {
+                "</code></pre>\n"
+            } else {
+                "<pre><code>"
+            });
+            in_code = !in_code;
+        } else if in_code {
+            out.push_str(&escape_html(line));
+            out.push('\n');
+        } else if line.starts_with('#') {
+            flush(out, &mut paragraph);
+            let heading = line.trim_start_matches('#').trim();
+            out.push_str(&format!("<h4>{}</h4>\n", inline_html(heading)));
+        } else if line.trim().is_empty() {
+            flush(out, &mut paragraph);
+        } else {
+            paragraph.push(line.trim());
+        }
+    }
+
+    if in_code {
+        out.push_str("</code></pre>\n");
+    }
+    flush(out, &mut paragraph);
+}
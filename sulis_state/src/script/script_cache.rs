@@ -55,6 +55,38 @@ pub fn setup() -> Result<()> {
     Ok(())
 }
 
+/// Re-reads the module's script files and rebuilds the state of each script
+/// that has changed, returning the IDs of the rebuilt scripts.  Effects and
+/// callbacks refer to scripts by ID, so they use the new state from their next
+/// call.  A script that fails to load keeps its previous state.
+pub fn reload() -> Vec<String> {
+    let start = Instant::now();
+    let mut reloaded = Vec::new();
+    for id in Module::reload_scripts() {
+        let script = match Module::script(&id) {
+            None => continue,
+            Some(script) => script,
+        };
+
+        let mut state = ScriptState::new();
+        if let Err(e) = state.load(&id, &script) {
+            warn!("Unable to reload script '{}'", id);
+            warn!("{}", e);
+            continue;
+        }
+
+        SCRIPT_CACHE.with(|cache| cache.borrow_mut().insert(id.to_string(), Rc::new(state)));
+        reloaded.push(id);
+    }
+
+    info!(
+        "Reloaded scripts {:?} in {:.3} millis",
+        reloaded,
+        get_elapsed_millis(start.elapsed())
+    );
+    reloaded
+}
+
 pub fn set_report_enabled(enabled: bool) {
     REPORTING.with(|r| r.set(enabled));
 }
@@ -90,6 +122,16 @@ pub fn ai_script(parent: &Rc<RefCell<EntityState>>, func: &str) -> Result<ai::St
     exec_func(&script, func, parent)
 }
 
+pub fn ai_override_script(
+    parent: &Rc<RefCell<EntityState>>,
+    script: &str,
+    func: &str,
+    actions: Vec<ai::UtilityAction>,
+) -> Result<Option<ai::State>> {
+    let parent = ScriptEntity::from(parent);
+    exec_func(script, func, (parent, actions))
+}
+
 pub fn entity_script<T>(
     parent: &Rc<RefCell<EntityState>>,
     targets: ScriptEntitySet,
@@ -217,7 +259,7 @@ where
 fn get_script_id_from_entity(entity: &Rc<RefCell<EntityState>>) -> Result<String> {
     let entity = entity.borrow();
     let id = entity.unique_id();
-    match &entity.actor.actor.ai {
+    match entity.ai_template() {
         None => Err(rlua::Error::ToLuaConversionError {
             from: "Entity",
             to: "Script",
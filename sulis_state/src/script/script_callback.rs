@@ -806,7 +806,7 @@ pub struct ScriptHitKind {
 }
 
 #[derive(Clone)]
-struct DamageEntry {
+pub(crate) struct DamageEntry {
     kind: &'static str,
     amount: u32,
 }
@@ -29,7 +29,7 @@ use sulis_core::resource::ResourceSet;
 use sulis_core::util::ExtInt;
 use sulis_module::{
     Actor, Attack, AttackKind, Attribute, DamageKind, Faction, HitFlags, HitKind, ImageLayer,
-    InventoryBuilder, MOVE_TO_THRESHOLD,
+    InventoryBuilder, PartyAIPreset, PositionWeights, MOVE_TO_THRESHOLD,
 };
 
 /// Represents a single entity for Lua scripts.  Also can represent an invalid,
@@ -269,6 +269,52 @@ use sulis_module::{
 /// # `has_visibility(target: ScriptEntity) -> Bool`
 /// Returns true if this entity can see the `target`, false otherwise.
 ///
+/// # `tactical_positions(target: ScriptEntity (Optional), count: Int (Optional)) -> Table`
+/// Scores every position this entity could move to this turn, and returns a
+/// table of the `count` best positions (or all positions if not specified),
+/// best first.  Each position is a table with `x`, `y`, `score`, `moves`,
+/// `flanking`, `threat`, `surface_danger`, `line_of_sight`, `openness`,
+/// and `crowding` fields.  Flanking and line of sight are only checked against the `target`
+/// if one is specified.  Scores use the position weights from this entity's
+/// AI template, or the defaults if it has none.
+///
+/// # `tactical_score(point: Table, target: ScriptEntity (Optional)) -> Table`
+/// Scores the single position `point`, a table of the form `{x: x_coord,
+/// y: y_coord}`, as with `tactical_positions`, but without any movement
+/// cost.  Returns nil if this entity would not fit inside the area at the
+/// point.
+///
+/// # `ai_role() -> String`
+/// Returns this entity's role in its encounter group, one of `Tank`,
+/// `Ranged`, or `Healer`.  The role is set in the AI template, or inferred
+/// from the entity's attacks.
+///
+/// # `group_focus() -> ScriptEntity`
+/// Returns the hostile that this entity's encounter group is focusing its
+/// attacks on, or nil if there is none.
+///
+/// # `is_group_retreating() -> Bool`
+/// Returns true if this entity's encounter group is retreating, false
+/// otherwise.
+///
+/// # `party_ai() -> String`
+/// Returns the AI preset controlling this party member in combat, one of
+/// `Aggressive`, `Defensive`, `Support`, or `Passive`, or nil if it is
+/// controlled by the player.
+///
+/// # `set_party_ai(preset: String (Optional))`
+/// Sets the AI preset controlling this party member in combat, as with
+/// `party_ai`.  If `preset` is not specified, the player controls this
+/// party member.  Has no effect if this entity is not in the party.
+///
+/// # `has_schedule() -> Bool`
+/// Returns true if this entity follows an out of combat schedule, walking
+/// patrol routes or moving between locations by the time of day.
+///
+/// # `clear_schedule()`
+/// Stops this entity following its out of combat schedule, so it stays
+/// where it is.
+///
 /// # `can_move() -> Bool`
 /// Returns true if this entity can move at all (even 1 square), false otherwise.
 ///
@@ -1018,6 +1064,92 @@ impl UserData for ScriptEntity {
             Ok(result)
         });
 
+        methods.add_method(
+            "tactical_positions",
+            |_, entity, (target, count): (Option<ScriptEntity>, Option<usize>)| {
+                let parent = entity.try_unwrap()?;
+                let target = match target {
+                    None => None,
+                    Some(target) => Some(target.try_unwrap()?),
+                };
+                let weights = position_weights(&parent);
+                let mut positions = ai::evaluate_positions(&parent, &weights, target.as_ref());
+                if let Some(count) = count {
+                    positions.truncate(count);
+                }
+                Ok(positions)
+            },
+        );
+
+        methods.add_method(
+            "tactical_score",
+            |_, entity, (point, target): (HashMap<String, i32>, Option<ScriptEntity>)| {
+                let (x, y) = unwrap_point(point)?;
+                let parent = entity.try_unwrap()?;
+                let target = match target {
+                    None => None,
+                    Some(target) => Some(target.try_unwrap()?),
+                };
+                let weights = position_weights(&parent);
+                Ok(ai::score_position(&parent, &weights, x, y, target.as_ref()))
+            },
+        );
+
+        methods.add_method("ai_role", |_, entity, ()| {
+            let entity = entity.try_unwrap()?;
+            let role = ai::GroupState::role(&entity.borrow());
+            Ok(format!("{:?}", role))
+        });
+
+        methods.add_method("group_focus", |_, entity, ()| {
+            let entity = entity.try_unwrap()?;
+            let group = ai::GroupState::for_entity(&entity);
+            Ok(group
+                .and_then(|group| group.focus)
+                .map(|focus| ScriptEntity::from(&focus)))
+        });
+
+        methods.add_method("is_group_retreating", |_, entity, ()| {
+            let entity = entity.try_unwrap()?;
+            let group = ai::GroupState::for_entity(&entity);
+            Ok(group.is_some_and(|group| group.retreating))
+        });
+
+        methods.add_method("party_ai", |_, entity, ()| {
+            let entity = entity.try_unwrap()?;
+            let preset = entity.borrow().party_ai();
+            Ok(preset.map(|preset| preset.name()))
+        });
+
+        methods.add_method("set_party_ai", |_, entity, preset: Option<String>| {
+            let entity = entity.try_unwrap()?;
+            let preset = match preset {
+                None => None,
+                Some(preset) => match PartyAIPreset::from_name(&preset) {
+                    None => {
+                        warn!("Invalid party AI preset '{}' in script", preset);
+                        return Ok(());
+                    }
+                    Some(preset) => Some(preset),
+                },
+            };
+
+            entity.borrow_mut().set_party_ai(preset);
+            Ok(())
+        });
+
+        methods.add_method("has_schedule", |_, entity, ()| {
+            let entity = entity.try_unwrap()?;
+            let has_schedule = entity.borrow().schedule().is_some();
+            Ok(has_schedule)
+        });
+
+        methods.add_method("clear_schedule", |_, entity, ()| {
+            let entity = entity.try_unwrap()?;
+            entity.borrow_mut().set_schedule(None);
+            Ok(())
+        });
+
         methods.add_method("can_move", |_, entity, ()| {
             let parent = entity.try_unwrap()?;
             let result = parent.borrow().can_move();
@@ -1219,7 +1351,9 @@ impl UserData for ScriptEntity {
                         AttackKind::Dummy,
                     );
                     let damage = &attack.damage;
-                    rules.roll_damage(damage, &parent.armor, &parent.resistance, 1.0)
+                    GameState::with_random(|rand| {
+                        rules.roll_damage(rand, damage, &parent.armor, &parent.resistance, 1.0)
+                    })
                 };
 
                 if !damage.is_empty() {
@@ -1503,6 +1637,14 @@ impl UserData for ScriptEntity {
     }
 }
 
+fn position_weights(entity: &Rc<RefCell<EntityState>>) -> PositionWeights {
+    let ai = entity.borrow().ai_template();
+    match ai.as_ref().and_then(|ai| ai.utility.as_ref()) {
+        None => PositionWeights::default(),
+        Some(utility) => utility.weights.position.clone(),
+    }
+}
+
 pub fn unwrap_point(point: HashMap<String, i32>) -> Result<(i32, i32)> {
     let x = match point.get("x") {
         None => {
@@ -24,7 +24,7 @@ use rlua::{self, Context, UserData, UserDataMethods};
 use crate::script::{Result, ScriptActiveSurface, ScriptEntity};
 use crate::{EntityState, GameState};
 use sulis_module::Faction;
-use sulis_core::util::{gen_rand, invalid_data_error};
+use sulis_core::util::invalid_data_error;
 
 /// Represents a set of ScriptEntities, which can be created from a variety of
 /// sources.  This is passed to many script functions as a `targets` variable.
@@ -245,7 +245,7 @@ impl UserData for ScriptEntitySet {
                 .affected_points
                 .iter()
                 .filter_map(|p| {
-                    let roll = gen_rand(0.0, 1.0);
+                    let roll = GameState::with_random(|rand| rand.gen(0.0, 1.0));
                     if roll > frac {
                         None
                     } else {
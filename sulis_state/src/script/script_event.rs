@@ -0,0 +1,154 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Lua script subscriptions to the global gameplay events published on the
+//! `EventBus`.  Subscriptions are created with `game:subscribe()`, and are
+//! stored in save files.
+
+use std::cell::RefCell;
+
+use rlua::{self, Context, ToLua, Value};
+
+use crate::script::{Script, ScriptEntity, ScriptHitKind};
+use crate::{ChangeListener, EventBus, GameEvent};
+
+const LISTENER_ID: &str = "script_subscriptions";
+
+thread_local! {
+    static SUBSCRIPTIONS: RefCell<Vec<ScriptSubscription>> = RefCell::new(Vec::new());
+}
+
+/// A request for the `func` in the script with the `script` ID to be called
+/// whenever an event with the `event` name occurs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptSubscription {
+    pub event: String,
+    pub script: String,
+    pub func: String,
+}
+
+pub struct ScriptSubscriptions;
+
+impl ScriptSubscriptions {
+    /// Replaces all current subscriptions with the specified ones, and starts
+    /// delivering events to them.  Called whenever a game is started or
+    /// loaded.
+    pub(crate) fn load(subscriptions: Vec<ScriptSubscription>) {
+        SUBSCRIPTIONS.with(|subs| *subs.borrow_mut() = subscriptions);
+        EventBus::subscribe(ChangeListener::new(LISTENER_ID, Box::new(deliver)));
+    }
+
+    /// Returns a copy of all current subscriptions, in the order they were
+    /// created
+    pub fn subscriptions() -> Vec<ScriptSubscription> {
+        SUBSCRIPTIONS.with(|subs| subs.borrow().clone())
+    }
+
+    /// Adds the specified subscription.  Returns false and does nothing if
+    /// an identical subscription already exists.
+    pub fn subscribe(subscription: ScriptSubscription) -> bool {
+        SUBSCRIPTIONS.with(|subs| {
+            let mut subs = subs.borrow_mut();
+            if subs.contains(&subscription) {
+                return false;
+            }
+            subs.push(subscription);
+            true
+        })
+    }
+
+    /// Removes the specified subscription.  Returns false if it did not exist.
+    pub fn unsubscribe(subscription: &ScriptSubscription) -> bool {
+        SUBSCRIPTIONS.with(|subs| {
+            let mut subs = subs.borrow_mut();
+            let len = subs.len();
+            subs.retain(|sub| sub != subscription);
+            subs.len() != len
+        })
+    }
+}
+
+fn deliver(event: &GameEvent) {
+    let subs: Vec<ScriptSubscription> = SUBSCRIPTIONS.with(|subs| {
+        subs.borrow()
+            .iter()
+            .filter(|sub| sub.event == event.name())
+            .cloned()
+            .collect()
+    });
+
+    for sub in subs {
+        Script::trigger(&sub.script, &sub.func, ScriptGameEvent(event.clone()));
+    }
+}
+
+/// A `GameEvent` passed to a subscribed script function as a table.  The
+/// table always contains the event `name`, and additional fields depending
+/// on the event.  Entities are passed as `ScriptEntity`s, which may no
+/// longer be valid by the time the event is delivered.
+pub struct ScriptGameEvent(pub GameEvent);
+
+impl<'lua> ToLua<'lua> for ScriptGameEvent {
+    fn to_lua(self, lua: Context<'lua>) -> rlua::Result<Value<'lua>> {
+        use GameEvent::*;
+        let table = lua.create_table()?;
+        table.set("name", self.0.name())?;
+
+        match self.0 {
+            EntityDamaged {
+                entity,
+                attacker,
+                hit_kind,
+                damage,
+            } => {
+                table.set("entity", ScriptEntity::new(entity))?;
+                table.set("attacker", ScriptEntity::new(attacker))?;
+                table.set("hit", ScriptHitKind::new(hit_kind, damage))?;
+            }
+            EntityDied { entity } => {
+                table.set("entity", ScriptEntity::new(entity))?;
+            }
+            EffectApplied { entity, name, .. } | EffectRemoved { entity, name, .. } => {
+                table.set("entity", ScriptEntity::new(entity))?;
+                table.set("effect", name)?;
+            }
+            AbilityActivated { entity, ability } => {
+                table.set("entity", ScriptEntity::new(entity))?;
+                table.set("ability", ability)?;
+            }
+            ItemLooted { item, quantity } => {
+                table.set("item", item)?;
+                table.set("quantity", quantity)?;
+            }
+            QuestStateChanged {
+                quest,
+                entry,
+                state,
+            } => {
+                table.set("quest", quest)?;
+                table.set("entry", entry)?;
+                table.set("state", format!("{:?}", state))?;
+            }
+            AreaEntered { area } => {
+                table.set("area", area)?;
+            }
+            CombatStarted | CombatEnded => (),
+        }
+
+        Ok(Value::Table(table))
+    }
+}
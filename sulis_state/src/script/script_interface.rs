@@ -20,6 +20,8 @@ use std::rc::Rc;
 
 use rlua::{self, UserData, UserDataMethods};
 
+use crate::event_bus::EVENT_NAMES;
+use crate::save_file::{self, AutosaveTrigger};
 use crate::script::*;
 use crate::{animation::Anim, AreaState, EntityState, GameState, ItemState, Location};
 use sulis_core::config::Config;
@@ -142,6 +144,18 @@ use sulis_module::{Faction, Module, OnTrigger, Time};
 /// Logs the specified string to the game's output at info level.  This is primarily useful
 /// for debugging purposes.
 ///
+/// # `reload_scripts() -> String`
+/// Re-reads all script files from the module directories, and rebuilds any scripts that
+/// have changed.  Returns a message listing the reloaded scripts.  Intended for use from
+/// the console while developing scripts.
+///
+/// # `reload_resources() -> String`
+/// Re-reads the YAML for abilities, ability lists, classes, items, loot lists,
+/// props, conversations, actors, and encounters from the module directories.
+/// Changed resources are re-created, and all existing entities and items are
+/// updated to use the new definitions.  Returns a message with the number of
+/// changed resources.
+///
 /// # `ap_display_factor() -> Int`
 /// Gets the ap display factor, which is the factor that the internal AP representation is
 /// divided by when displayed.  Any AP values that are displayed to the user must be
@@ -165,6 +179,38 @@ use sulis_module::{Faction, Module, OnTrigger, Time};
 /// elapsed.  The game can normally achieve a comfortable 60 fps on the vast majority of
 /// hardware, but be aware that this is not always the case.
 ///
+/// # `subscribe(event: String, script_id: String, func: String)`
+/// Causes the specified `func` from the script with `script_id` to be called with a table
+/// describing the event whenever a gameplay event named `event` occurs, anywhere in the game.
+/// Subscriptions are stored in save files.  Subscribing the same function to the same event
+/// more than once has no additional effect.  Events are delivered at the start of the frame
+/// after they occur.  The table always contains the `name` of the event, along with:
+/// - `entity_damaged`: `entity`, `attacker`, and `hit`, a `ScriptHitKind`
+/// - `entity_died`: `entity`
+/// - `effect_applied`, `effect_removed`: `entity` and the `effect` name
+/// - `ability_activated`: `entity` and the `ability` ID
+/// - `item_looted`: the `item` ID and `quantity`, when taken into the party stash
+/// - `quest_state_changed`: `quest`, `entry` (nil for the quest as a whole), and `state`
+/// - `area_entered`: the `area` ID
+/// - `combat_started`, `combat_ended`: no additional fields
+///
+/// Entities may no longer be valid when the event is delivered, so check `is_valid()`.
+/// ## Examples
+/// ```lua
+/// function on_load(parent)
+///   game:subscribe("entity_died", "campaign", "on_entity_died")
+/// end
+///
+/// function on_entity_died(event)
+///   if not event.entity:is_valid() then return end
+///   game:log(event.entity:name() .. " has died")
+/// end
+/// ```
+///
+/// # `unsubscribe(event: String, script_id: String, func: String)`
+/// Removes a subscription previously created with `subscribe`.  Does nothing if there
+/// is no such subscription.
+///
 /// # `create_callback(parent: ScriptEntity, script: String) -> ScriptCallback`
 /// Creates a new script callback.  This callback will utilize the specified script
 /// file for all methods.  See `ScriptCallback` for more.
@@ -430,6 +476,7 @@ impl UserData for ScriptInterface {
             for member in GameState::party() {
                 member.borrow_mut().actor.init_day();
             }
+            save_file::request_autosave(AutosaveTrigger::Rest);
             Ok(())
         });
 
@@ -467,6 +514,27 @@ impl UserData for ScriptInterface {
             Ok(())
         });
 
+        methods.add_method(
+            "reload_resources",
+            |_, _, ()| match GameState::reload_resources() {
+                Ok(count) => Ok(format!("Reloaded {} resources", count)),
+                Err(e) => Err(rlua::Error::ToLuaConversionError {
+                    from: "YAML",
+                    to: "Resources",
+                    message: Some(format!("Unable to reload resources: {}", e)),
+                }),
+            },
+        );
+
+        methods.add_method("reload_scripts", |_, _, ()| {
+            let reloaded = script_cache::reload();
+            Ok(format!(
+                "Reloaded {} scripts: {}",
+                reloaded.len(),
+                reloaded.join(", ")
+            ))
+        });
+
         methods.add_method("ap_display_factor", |_, _, ()| {
             let rules = Module::rules();
             Ok(rules.display_ap)
@@ -502,6 +570,46 @@ impl UserData for ScriptInterface {
             },
         );
 
+        methods.add_method(
+            "subscribe",
+            |_, _, (event, script, func): (String, String, String)| {
+                if !EVENT_NAMES.contains(&event.as_str()) {
+                    return Err(rlua::Error::FromLuaConversionError {
+                        from: "String",
+                        to: "GameEvent",
+                        message: Some(format!(
+                            "Invalid event '{}'.  Must be one of {}",
+                            event,
+                            EVENT_NAMES.join(", ")
+                        )),
+                    });
+                }
+
+                if Module::script(&script).is_none() {
+                    warn!("Subscribing invalid script '{}' to '{}'", script, event);
+                }
+
+                ScriptSubscriptions::subscribe(ScriptSubscription {
+                    event,
+                    script,
+                    func,
+                });
+                Ok(())
+            },
+        );
+
+        methods.add_method(
+            "unsubscribe",
+            |_, _, (event, script, func): (String, String, String)| {
+                ScriptSubscriptions::unsubscribe(&ScriptSubscription {
+                    event,
+                    script,
+                    func,
+                });
+                Ok(())
+            },
+        );
+
         methods.add_method(
             "create_callback",
             |_, _, (parent, script): (ScriptEntity, String)| {
@@ -0,0 +1,308 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! Static checking of the module's Lua scripts against the script API.
+//! Each script is loaded to check for syntax errors and to find the functions
+//! it defines, and is then scanned for `object:method()` calls to methods that
+//! are not part of the `ScriptApi`.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::script::{ScriptApi, ScriptState};
+use sulis_module::{Module, ValidationError};
+
+/// Methods that may be called on Lua strings, such as `s:format(...)`
+const STRING_METHODS: [&str; 17] = [
+    "byte", "char", "dump", "find", "format", "gmatch", "gsub", "len", "lower", "match", "rep",
+    "reverse", "sub", "upper", "pack", "packsize", "unpack",
+];
+
+/// The result of loading and checking all of the module's scripts
+pub struct ScriptLint {
+    states: HashMap<String, ScriptState>,
+    errors: Vec<ValidationError>,
+}
+
+impl ScriptLint {
+    /// Loads and checks every script in `Module::all_scripts`
+    pub fn new() -> ScriptLint {
+        let api = ScriptApi::new().method_names();
+        let mut ids = Module::all_scripts();
+        ids.sort();
+
+        let mut lint = ScriptLint {
+            states: HashMap::new(),
+            errors: Vec::new(),
+        };
+
+        for id in ids {
+            let script = match Module::script(&id) {
+                None => continue,
+                Some(script) => script,
+            };
+
+            let mut state = ScriptState::new();
+            if let Err(e) = state.load(&id, &script) {
+                lint.error(&id, 0, format!("Unable to load script: {}", e));
+                continue;
+            }
+
+            let tokens = tokenize(&script);
+            let defined = defined_names(&tokens);
+            for (line, method) in method_calls(&tokens) {
+                if api.contains(&method)
+                    || defined.contains(&method)
+                    || STRING_METHODS.contains(&method.as_str())
+                {
+                    continue;
+                }
+
+                lint.error(&id, line, format!("Call to unknown method '{}'", method));
+            }
+
+            lint.states.insert(id, state);
+        }
+
+        lint
+    }
+
+    fn error(&mut self, id: &str, line: usize, message: String) {
+        let path = if line == 0 {
+            String::new()
+        } else {
+            format!("line {}", line)
+        };
+
+        self.errors.push(ValidationError {
+            file: format!("script '{}'", id),
+            path,
+            message,
+        });
+    }
+
+    /// All problems found in the scripts
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+
+    /// Returns true if the script with the specified ID loaded successfully
+    /// and defines a global function with the specified name
+    pub fn has_func(&self, id: &str, func: &str) -> bool {
+        match self.states.get(id) {
+            None => false,
+            Some(state) => state.has_func(func),
+        }
+    }
+}
+
+impl Default for ScriptLint {
+    fn default() -> Self {
+        ScriptLint::new()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Symbol(char),
+    Literal,
+}
+
+/// A minimal Lua lexer, producing each token along with its line number.
+/// Comments are skipped, and strings and numbers are only recorded as
+/// `Literal`s.
+fn tokenize(src: &str) -> Vec<(usize, Token)> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            i += 2;
+            match long_bracket_level(&chars, i) {
+                Some(level) => i = skip_long_bracket(&chars, i, level, &mut line),
+                None => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                }
+            }
+        } else if c == '"' || c == '\'' {
+            let start_line = line;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                if chars.get(i) == Some(&'\n') {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push((start_line, Token::Literal));
+        } else if c == '[' && long_bracket_level(&chars, i).is_some() {
+            let start_line = line;
+            let level = long_bracket_level(&chars, i).unwrap();
+            i = skip_long_bracket(&chars, i, level, &mut line);
+            tokens.push((start_line, Token::Literal));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let ident: String = chars[start..i].iter().collect();
+            tokens.push((line, Token::Ident(ident)));
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push((line, Token::Literal));
+        } else {
+            tokens.push((line, Token::Symbol(c)));
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// If a long bracket such as `[[` or `[==[` starts at the specified index,
+/// returns its level
+fn long_bracket_level(chars: &[char], start: usize) -> Option<usize> {
+    if chars.get(start) != Some(&'[') {
+        return None;
+    }
+
+    let mut level = 0;
+    while chars.get(start + 1 + level) == Some(&'=') {
+        level += 1;
+    }
+
+    if chars.get(start + 1 + level) == Some(&'[') {
+        Some(level)
+    } else {
+        None
+    }
+}
+
+/// Skips past the long bracket of the specified level starting at the
+/// specified index, returning the index just after its closing bracket
+fn skip_long_bracket(chars: &[char], start: usize, level: usize, line: &mut usize) -> usize {
+    let mut i = start + level + 2;
+    while i < chars.len() {
+        if chars[i] == '\n' {
+            *line += 1;
+        } else if chars[i] == ']' {
+            let mut end = i + 1;
+            while end < chars.len() && chars[end] == '=' {
+                end += 1;
+            }
+
+            if end - i - 1 == level && chars.get(end) == Some(&']') {
+                return end + 1;
+            }
+        }
+        i += 1;
+    }
+
+    i
+}
+
+fn ident(token: Option<&(usize, Token)>) -> Option<&str> {
+    match token {
+        Some((_, Token::Ident(ident))) => Some(ident),
+        _ => None,
+    }
+}
+
+fn is_symbol(token: Option<&(usize, Token)>, c: char) -> bool {
+    match token {
+        Some((_, Token::Symbol(symbol))) => *symbol == c,
+        _ => false,
+    }
+}
+
+/// Finds all method calls, such as `parent:targets()` or `s:format "x"`,
+/// returning the line and method name for each
+fn method_calls(tokens: &[(usize, Token)]) -> Vec<(usize, String)> {
+    let mut calls = Vec::new();
+    for (index, (line, token)) in tokens.iter().enumerate() {
+        if *token != Token::Symbol(':') {
+            continue;
+        }
+
+        // skip labels, of the form ::name::
+        if index > 0 && is_symbol(tokens.get(index - 1), ':') {
+            continue;
+        }
+
+        let name = match ident(tokens.get(index + 1)) {
+            None => continue,
+            Some(name) => name,
+        };
+
+        let next = tokens.get(index + 2);
+        let is_call = match next {
+            Some((_, Token::Literal)) => true,
+            _ => is_symbol(next, '(') || is_symbol(next, '{'),
+        };
+
+        if is_call {
+            calls.push((*line, name.to_string()));
+        }
+    }
+
+    calls
+}
+
+/// Finds the names of all functions defined by the script, including
+/// methods defined on the script's own tables with `function t:name()` or
+/// `t.name = function()`
+fn defined_names(tokens: &[(usize, Token)]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for (index, (_, token)) in tokens.iter().enumerate() {
+        match token {
+            Token::Ident(ref keyword) if keyword == "function" => {
+                let mut cur = index + 1;
+                while let Some(name) = ident(tokens.get(cur)) {
+                    names.insert(name.to_string());
+                    if is_symbol(tokens.get(cur + 1), '.') || is_symbol(tokens.get(cur + 1), ':') {
+                        cur += 2;
+                    } else {
+                        break;
+                    }
+                }
+            }
+            Token::Ident(ref name)
+                if is_symbol(tokens.get(index + 1), '=')
+                    && ident(tokens.get(index + 2)) == Some("function") =>
+            {
+                names.insert(name.to_string());
+            }
+            _ => (),
+        }
+    }
+
+    names
+}
@@ -18,9 +18,12 @@ use std::cell::{Cell, RefCell};
 use std::collections::{vec_deque::Iter, HashMap, HashSet, VecDeque};
 use std::rc::Rc;
 
+use crate::combat_log::{CombatLog, CombatLogKind};
+use crate::event_bus::{EventBus, GameEvent};
+use crate::save_file::{self, AutosaveTrigger};
 use crate::script::{CallbackData, FuncKind, TriggeredCallback};
 use crate::{AreaState, ChangeListener, ChangeListenerList, Effect, EntityState, GameState};
-use sulis_core::util::{gen_rand, Point};
+use sulis_core::util::Point;
 use sulis_module::{Faction, Module, Time, ROUND_TIME_MILLIS};
 
 fn add_campaign_elapsed_callback(cbs: &mut Vec<Rc<CallbackData>>) {
@@ -48,8 +51,24 @@ enum Entry {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct EncounterRef {
-    area_id: String,
-    encounter_index: usize,
+    pub(crate) area_id: String,
+    pub(crate) encounter_index: usize,
+
+    /// The highest total of maximum hit points seen for the group's
+    /// members, used to measure the group's remaining strength
+    #[serde(default)]
+    pub(crate) max_hp: i32,
+
+    #[serde(default)]
+    pub(crate) reinforcements_called: u32,
+
+    #[serde(default)]
+    pub(crate) retreating: bool,
+
+    /// The index of the hostile entity the group is focusing on.  Entity
+    /// indices change on load, so this is chosen again after loading.
+    #[serde(skip)]
+    pub(crate) focus: Option<usize>,
 }
 
 pub struct TurnManager {
@@ -237,11 +256,19 @@ impl TurnManager {
             EncounterRef {
                 area_id: area_id.to_string(),
                 encounter_index: enc_index,
+                max_hp: 0,
+                reinforcements_called: 0,
+                retreating: false,
+                focus: None,
             },
         );
         value
     }
 
+    pub(crate) fn ai_group_mut(&mut self, group: usize) -> Option<&mut EncounterRef> {
+        self.ai_groups.get_mut(&group)
+    }
+
     pub fn entity_checked(&self, index: usize) -> Option<Rc<RefCell<EntityState>>> {
         if index >= self.entities.len() {
             return None;
@@ -402,7 +429,7 @@ impl TurnManager {
             _ => unreachable!(),
         };
 
-        if current.borrow().is_party_member() {
+        if current.borrow().is_player_controlled() {
             GameState::set_selected_party_member(Rc::clone(current));
             area_state.set_default_range_indicator(Some(current), self.is_combat_active());
         } else {
@@ -630,8 +657,10 @@ impl TurnManager {
 
         if !active {
             self.end_combat();
+            EventBus::publish(GameEvent::CombatEnded);
         } else {
             self.initiate_combat();
+            EventBus::publish(GameEvent::CombatStarted);
         }
     }
 
@@ -689,6 +718,8 @@ impl TurnManager {
 
         let area = GameState::area_state();
         area.borrow_mut().set_range_indicator(None);
+
+        save_file::request_autosave(AutosaveTrigger::CombatEnd);
     }
 
     fn initiate_combat(&mut self) {
@@ -708,7 +739,8 @@ impl TurnManager {
                         .actor
                         .stats
                         .initiative;
-                    last_initiative = base + gen_rand(0, initiative_roll_max);
+                    let roll = GameState::with_random(|rand| rand.gen(0, initiative_roll_max));
+                    last_initiative = base + roll;
                     initiative[index] = 2 * last_initiative;
                 }
                 Entry::Effect(_) => {
@@ -906,6 +938,22 @@ impl TurnManager {
         let bonuses = self.effect(index).bonuses().clone();
         entity.borrow_mut().actor.add_effect(index, bonuses);
 
+        if self.effect(index).ui_visible {
+            CombatLog::push_at(
+                self.current_time(),
+                CombatLogKind::EffectApplied {
+                    target: entity.borrow().actor.actor.name.to_string(),
+                    effect: self.effect(index).name.to_string(),
+                },
+            );
+        }
+
+        EventBus::publish(GameEvent::EffectApplied {
+            entity: entity.borrow().index(),
+            effect: index,
+            name: self.effect(index).name.to_string(),
+        });
+
         index
     }
 
@@ -945,6 +993,25 @@ impl TurnManager {
             }
 
             cbs = effect.callbacks.clone();
+
+            let target = effect.entity.and_then(|i| self.entities.get(i)?.as_ref());
+            if let Some(target) = target.filter(|_| effect.ui_visible) {
+                CombatLog::push_at(
+                    self.current_time(),
+                    CombatLogKind::EffectRemoved {
+                        target: target.borrow().actor.actor.name.to_string(),
+                        effect: effect.name.to_string(),
+                    },
+                );
+            }
+
+            if let Some(entity) = effect.entity {
+                EventBus::publish(GameEvent::EffectRemoved {
+                    entity,
+                    effect: index,
+                    name: effect.name.to_string(),
+                });
+            }
         } else {
             cbs = Vec::new();
         }
@@ -0,0 +1,436 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+//! A harness for writing scripted playthrough tests of campaigns.
+//!
+//! A `CampaignTest` loads a campaign through `Module::load_resources`,
+//! starts it with `GameState::init`, and builds the same UI tree as the
+//! game, updated without a window through a `HeadlessDisplay`.  Tests may
+//! then move the party, talk to and attack entities, and step through
+//! conversations before asserting on quest state, flags, and party stats.
+//!
+//! All game state is thread local, so each test should create its own
+//! `CampaignTest`.
+
+#[macro_use]
+extern crate log;
+
+use std::cell::RefCell;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use sulis_core::config::Config;
+use sulis_core::io::{HeadlessDisplay, MainLoopUpdater, IO};
+use sulis_core::resource::ResourceSet;
+use sulis_core::serde_yaml;
+use sulis_core::ui::{self, Widget};
+use sulis_core::util::{invalid_data_error, ReproducibleRandom};
+use sulis_module::{on_trigger::QuestEntryState, Actor, ActorBuilder, Module, OnTrigger};
+use sulis_state::{
+    script::{entity_with_id, ScriptState},
+    ChangeListener, EntityState, EventBus, GameEvent, GameState, NewGameOptions,
+};
+use sulis_view::{dialog_window::DialogWindow, GameMainLoopUpdater, RootView};
+
+/// The number of millis each frame is updated with by `run_frames`
+pub const FRAME_MILLIS: u32 = 16;
+
+/// The maximum amount of time, in millis, that actions such as moving the
+/// party will wait for their animations to complete
+pub const MAX_WAIT_MILLIS: u32 = 60_000;
+
+/// Loads the core resources along with the specified campaign and any mods.
+/// `root_dir` is the directory containing the `data` directory, and all other
+/// directories are relative to it.
+pub fn load_campaign(root_dir: &Path, campaign_dir: &str, mods: &[&str]) -> Result<(), Error> {
+    let mut config = Config::get_clone();
+    config.resources.directory = path_string(root_dir, &config.resources.directory);
+    config.resources.campaigns_directory =
+        path_string(root_dir, &config.resources.campaigns_directory);
+    config.resources.mods_directory = path_string(root_dir, &config.resources.mods_directory);
+    let data_dir = config.resources.directory.to_string();
+    Config::set(config);
+
+    let mut dirs = vec![data_dir, path_string(root_dir, campaign_dir)];
+    for mod_dir in mods {
+        dirs.push(path_string(root_dir, mod_dir));
+    }
+
+    info!("Reading test resources from '{:?}'", dirs);
+    let yaml = ResourceSet::load_resources(dirs.clone())?;
+    Module::load_resources(yaml, dirs)
+}
+
+/// Reads a character file, in the same format as the characters created by
+/// the character builder, and creates the actor from it.  A campaign must
+/// already be loaded.
+pub fn read_character(path: &Path) -> Result<Rc<Actor>, Error> {
+    let data = fs::read_to_string(path)?;
+    let builder: ActorBuilder = match serde_yaml::from_str(&data) {
+        Ok(builder) => builder,
+        Err(e) => return invalid_data_error(&format!("{}", e)),
+    };
+
+    Ok(Rc::new(Module::load_actor(builder)?))
+}
+
+fn path_string(root_dir: &Path, dir: &str) -> String {
+    let mut path = PathBuf::from(root_dir);
+    path.push(dir);
+    path.to_string_lossy().to_string()
+}
+
+/// A running campaign, driven one frame at a time.  Actions are performed
+/// through the same public APIs the game's UI uses, and then the game is
+/// updated until any resulting animations have completed.
+pub struct CampaignTest {
+    view: Rc<RefCell<RootView>>,
+    updater: GameMainLoopUpdater,
+    root: Rc<RefCell<Widget>>,
+    io: HeadlessDisplay,
+    render: bool,
+    total_millis: u32,
+    events: Rc<RefCell<Vec<GameEvent>>>,
+}
+
+impl CampaignTest {
+    /// Loads the campaign and starts it with the character from the
+    /// specified file.  See `load_campaign` and `read_character`.
+    pub fn new(root_dir: &Path, campaign_dir: &str, character: &Path) -> Result<Self, Error> {
+        load_campaign(root_dir, campaign_dir, &[])?;
+        let pc = read_character(character)?;
+        CampaignTest::start(pc)
+    }
+
+    /// Starts the currently loaded campaign with the specified player
+    /// character, and runs frames until any campaign start and area load
+    /// triggers have been handled.
+    pub fn start(pc_actor: Rc<Actor>) -> Result<Self, Error> {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let events_ref = Rc::clone(&events);
+        EventBus::subscribe(ChangeListener::new(
+            "campaign_test",
+            Box::new(move |event| events_ref.borrow_mut().push(event.clone())),
+        ));
+
+        GameState::init(pc_actor, NewGameOptions::default())?;
+
+        let view = RootView::new();
+        let updater = GameMainLoopUpdater::new(&view);
+        let root = ui::create_ui_tree(view.clone());
+
+        let mut test = CampaignTest {
+            view,
+            updater,
+            root,
+            io: HeadlessDisplay::new(),
+            render: false,
+            total_millis: 0,
+            events,
+        };
+        test.wait()?;
+        Ok(test)
+    }
+
+    /// Reseeds the gameplay random generator, so that all rolls from this
+    /// point on are reproducible
+    pub fn set_seed(&mut self, seed: u128) {
+        GameState::with_random(|rand| *rand = ReproducibleRandom::new(Some(seed)));
+    }
+
+    /// Sets whether each frame is also drawn by the headless display.  This
+    /// is off by default, as nothing in the game state depends on it.
+    pub fn set_render(&mut self, render: bool) {
+        self.render = render;
+    }
+
+    /// The headless display the UI is drawn to when rendering is enabled
+    pub fn io(&mut self) -> &mut HeadlessDisplay {
+        &mut self.io
+    }
+
+    pub fn root(&self) -> &Rc<RefCell<Widget>> {
+        &self.root
+    }
+
+    pub fn view(&self) -> &Rc<RefCell<RootView>> {
+        &self.view
+    }
+
+    /// Returns all gameplay events that have been delivered since the
+    /// campaign was started, oldest first
+    pub fn events(&self) -> Vec<GameEvent> {
+        self.events.borrow().clone()
+    }
+
+    /// Returns true if the game has requested an exit, such as when
+    /// returning to the main menu
+    pub fn is_exit(&self) -> bool {
+        self.updater.is_exit()
+    }
+
+    /// Updates the game and UI by a single frame of the specified millis
+    pub fn update(&mut self, millis: u32) -> Result<(), Error> {
+        self.total_millis += millis;
+
+        self.io.process_input(Rc::clone(&self.root));
+        self.updater.update(&self.root, millis);
+        Widget::update(&self.root, millis)?;
+
+        if self.render {
+            self.io.render_output(self.root.borrow(), self.total_millis);
+        }
+
+        Ok(())
+    }
+
+    /// Runs the specified number of frames of `FRAME_MILLIS` each
+    pub fn run_frames(&mut self, frames: u32) -> Result<(), Error> {
+        for _ in 0..frames {
+            self.update(FRAME_MILLIS)?;
+        }
+        Ok(())
+    }
+
+    /// Runs frames for the specified amount of game time
+    pub fn run_millis(&mut self, millis: u32) -> Result<(), Error> {
+        self.run_frames((millis + FRAME_MILLIS - 1) / FRAME_MILLIS)
+    }
+
+    /// Runs frames until there are no blocking animations, such as moves
+    /// and attacks, or pending UI callbacks remaining.  Returns an error if
+    /// this takes longer than `MAX_WAIT_MILLIS`
+    pub fn wait(&mut self) -> Result<(), Error> {
+        let mut elapsed = 0;
+        loop {
+            self.update(FRAME_MILLIS)?;
+
+            if !GameState::has_any_blocking_animations() && !GameState::has_ui_callbacks() {
+                return Ok(());
+            }
+
+            elapsed += FRAME_MILLIS;
+            if elapsed > MAX_WAIT_MILLIS {
+                return invalid_data_error("Timed out waiting for animations to complete");
+            }
+        }
+    }
+
+    /// Returns the player character
+    pub fn player(&self) -> Rc<RefCell<EntityState>> {
+        GameState::player()
+    }
+
+    /// Returns the entity in the current area with the specified unique ID
+    pub fn entity(&self, id: &str) -> Result<Rc<RefCell<EntityState>>, Error> {
+        match entity_with_id(id.to_string()) {
+            None => invalid_data_error(&format!("No entity with ID '{}'", id)),
+            Some(entity) => Ok(entity),
+        }
+    }
+
+    /// Moves the entire party towards the specified point in the current
+    /// area using the party formation, waiting for the move to complete
+    pub fn move_party(&mut self, x: f32, y: f32) -> Result<(), Error> {
+        let party = GameState::party();
+        let to_ignore = entities_to_ignore();
+        let formation = GameState::party_formation();
+        formation.borrow().move_group(&party, to_ignore, x, y);
+
+        self.wait()
+    }
+
+    /// Moves the specified entity to within `dist` of the specified point,
+    /// waiting for the move to complete.  Returns an error if no path exists
+    pub fn move_entity(
+        &mut self,
+        entity: &Rc<RefCell<EntityState>>,
+        x: f32,
+        y: f32,
+        dist: f32,
+    ) -> Result<(), Error> {
+        if !GameState::move_towards_point(entity, entities_to_ignore(), x, y, dist, None) {
+            return invalid_data_error(&format!("Unable to move to {},{}", x, y));
+        }
+
+        self.wait()
+    }
+
+    /// Spawns the actor with the specified ID at the specified point in the
+    /// current area and faction, just as the `spawn_actor_at` script
+    /// function does, and then waits for any resulting combat to start
+    pub fn spawn_actor(
+        &mut self,
+        id: &str,
+        x: i32,
+        y: i32,
+        faction: &str,
+    ) -> Result<Rc<RefCell<EntityState>>, Error> {
+        let script = format!(
+            "local entity = game:spawn_actor_at('{}', {}, {}, '{}')\n\
+             if entity:is_valid() then return entity:id() else return '' end",
+            id, x, y, faction
+        );
+
+        let uid = match ScriptState::new().console(script, &GameState::party()) {
+            Err(e) => return invalid_data_error(&format!("Error spawning '{}': {}", id, e)),
+            Ok(uid) => uid,
+        };
+        if uid.is_empty() {
+            return invalid_data_error(&format!("Unable to spawn '{}' at {},{}", id, x, y));
+        }
+
+        let entity = self.entity(&uid)?;
+        self.wait()?;
+        Ok(entity)
+    }
+
+    /// Has the player start a conversation with the entity with the
+    /// specified ID, just as when the player clicks on them
+    pub fn talk_to(&mut self, id: &str) -> Result<(), Error> {
+        let target = self.entity(id)?;
+
+        let convo = match target.borrow().actor.actor.conversation {
+            None => return invalid_data_error(&format!("Entity '{}' has no conversation", id)),
+            Some(ref convo) => convo.id.to_string(),
+        };
+
+        let cb = OnTrigger::StartConversation(convo);
+        GameState::add_ui_callback(vec![cb], &self.player(), &target);
+        self.wait()
+    }
+
+    /// Returns the widget of the open dialog window, if there is one
+    pub fn dialog_window(&self) -> Option<Rc<RefCell<Widget>>> {
+        Widget::get_child_with_name(&self.root, sulis_view::dialog_window::NAME)
+    }
+
+    /// Returns the ID of the conversation node currently being shown, or
+    /// `None` if no conversation is in progress
+    pub fn dialog_node(&self) -> Option<String> {
+        let widget = self.dialog_window()?;
+        let window = Widget::kind_mut::<DialogWindow>(&widget);
+        Some(window.cur_node().to_string())
+    }
+
+    /// Returns the text of each response currently shown to the player
+    pub fn dialog_responses(&self) -> Vec<String> {
+        let widget = match self.dialog_window() {
+            None => return Vec::new(),
+            Some(widget) => widget,
+        };
+        let window = Widget::kind_mut::<DialogWindow>(&widget);
+        let responses = window.viewable_responses();
+        responses.into_iter().map(|(_, text)| text).collect()
+    }
+
+    /// Selects the response with the specified index among those shown to
+    /// the player, as returned by `dialog_responses`
+    pub fn choose_response(&mut self, index: usize) -> Result<(), Error> {
+        let widget = match self.dialog_window() {
+            None => return invalid_data_error("No conversation is in progress"),
+            Some(widget) => widget,
+        };
+
+        let window = Widget::kind_mut::<DialogWindow>(&widget);
+        let response = match window.viewable_responses().get(index) {
+            None => return invalid_data_error(&format!("Invalid response index {}", index)),
+            Some((response, _)) => *response,
+        };
+
+        window.select_response(&widget, response);
+        self.wait()
+    }
+
+    /// Selects the first response shown to the player whose text contains
+    /// the specified string
+    pub fn choose_response_with_text(&mut self, text: &str) -> Result<(), Error> {
+        let index = self
+            .dialog_responses()
+            .iter()
+            .position(|r| r.contains(text));
+        match index {
+            None => invalid_data_error(&format!("No response containing '{}'", text)),
+            Some(index) => self.choose_response(index),
+        }
+    }
+
+    /// Has the player attack the entity with the specified ID, waiting for
+    /// the attack to complete.  Returns an error if the player is unable to
+    /// attack the target from their current position
+    pub fn attack(&mut self, id: &str) -> Result<(), Error> {
+        let target = self.entity(id)?;
+        let pc = self.player();
+
+        let area_state = GameState::area_state();
+        if !pc.borrow().can_attack(&target, &area_state.borrow()) {
+            return invalid_data_error(&format!("Unable to attack '{}'", id));
+        }
+
+        EntityState::attack(&pc, &target, None, true);
+        self.wait()
+    }
+
+    pub fn quest_state(&self, quest: &str) -> QuestEntryState {
+        GameState::get_quest_state(quest.to_string())
+    }
+
+    pub fn quest_entry_state(&self, quest: &str, entry: &str) -> QuestEntryState {
+        GameState::get_quest_entry_state(quest.to_string(), entry.to_string())
+    }
+
+    /// Returns the value of the custom flag on the player, if it is set
+    pub fn player_flag(&self, flag: &str) -> Option<String> {
+        self.player().borrow().get_custom_flag(flag)
+    }
+
+    /// Returns the value of the custom flag on the entity with the specified
+    /// ID, if the entity exists and the flag is set
+    pub fn entity_flag(&self, id: &str, flag: &str) -> Option<String> {
+        let entity = entity_with_id(id.to_string())?;
+        let entity = entity.borrow();
+        entity.get_custom_flag(flag)
+    }
+
+    pub fn party_coins(&self) -> i32 {
+        GameState::party_coins()
+    }
+
+    pub fn party_size(&self) -> usize {
+        GameState::party().len()
+    }
+
+    pub fn player_xp(&self) -> u32 {
+        self.player().borrow().actor.xp()
+    }
+
+    pub fn player_hp(&self) -> i32 {
+        self.player().borrow().actor.hp()
+    }
+}
+
+fn entities_to_ignore() -> Vec<usize> {
+    if GameState::is_combat_active() {
+        Vec::new()
+    } else {
+        GameState::party()
+            .iter()
+            .map(|e| e.borrow().index())
+            .collect()
+    }
+}
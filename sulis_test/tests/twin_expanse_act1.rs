@@ -0,0 +1,132 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::path::{Path, PathBuf};
+
+use sulis_module::{on_trigger::QuestEntryState, DamageKind, HitKind};
+use sulis_state::{GameEvent, GameState, SaveFile};
+use sulis_test::CampaignTest;
+
+fn start() -> CampaignTest {
+    let root_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let mut character = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    character.push("tests/characters/fighter.yml");
+
+    let mut test = CampaignTest::new(&root_dir, "campaigns/twin_expanse_act1", &character)
+        .expect("Unable to start campaign");
+    test.set_seed(1234);
+    test
+}
+
+#[test]
+fn intro() {
+    let mut test = start();
+
+    assert_eq!(
+        test.quest_entry_state("the_rockslide", "start"),
+        QuestEntryState::Visible
+    );
+    assert!(test.party_coins() >= 5000);
+    assert!(test.player_xp() >= 600);
+    assert_eq!(test.party_size(), 1);
+
+    let events = test.events();
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, GameEvent::AreaEntered { .. })));
+    assert!(events.iter().any(|e| match e {
+        GameEvent::QuestStateChanged { quest, entry, .. } =>
+            quest == "the_rockslide" && entry.as_deref() == Some("start"),
+        _ => false,
+    }));
+
+    assert_eq!(test.dialog_node(), Some("start01".to_string()));
+    test.choose_response(0).unwrap();
+    assert_eq!(test.dialog_node(), Some("start02".to_string()));
+    test.choose_response(0).unwrap();
+    assert_eq!(test.dialog_node(), Some("start03".to_string()));
+    test.choose_response_with_text("Go").unwrap();
+    assert_eq!(test.dialog_node(), None);
+}
+
+#[test]
+fn combat() {
+    let mut test = start();
+    test.choose_response(0).unwrap();
+    test.choose_response(0).unwrap();
+    test.choose_response_with_text("Go").unwrap();
+
+    let rat = test.spawn_actor("rat", 16, 14, "Hostile").unwrap();
+    let rat_index = rat.borrow().index();
+    let rat_id = rat.borrow().unique_id().to_string();
+    assert!(GameState::is_combat_active());
+    assert!(GameState::is_pc_current());
+
+    let pc = test.player();
+    test.move_entity(&pc, 16.0, 14.0, 1.5).unwrap();
+    assert_ne!(pc.borrow().location.to_point(), (10, 13).into());
+
+    test.attack(&rat_id).unwrap();
+
+    let events = test.events();
+    let damage: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            GameEvent::EntityDamaged {
+                entity,
+                hit_kind,
+                damage,
+                ..
+            } if *entity == rat_index => Some((*hit_kind, damage.clone())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        damage,
+        vec![(HitKind::Hit, vec![(DamageKind::Slashing, 26)])]
+    );
+
+    assert!(rat.borrow().actor.is_dead());
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, GameEvent::EntityDied { entity } if *entity == rat_index)));
+    assert!(matches!(events.last(), Some(GameEvent::CombatEnded)));
+}
+
+#[test]
+fn save_round_trip() {
+    let _test = start();
+
+    // advance the random generator so its state uses the full 128 bits
+    let expected = GameState::with_random(|rand| {
+        rand.gen(0, 100);
+        rand.clone().gen(0, u64::MAX)
+    });
+
+    let json = SaveFile::create().to_json().unwrap();
+
+    // saves written before versioning have no version in their meta data
+    assert!(json.starts_with(r#"{"meta":{"version":1,"#));
+    let json = json.replacen(r#""version":1,"#, "", 1);
+
+    let state = SaveFile::from_json(&json).unwrap().into_state();
+    GameState::load(state).unwrap();
+
+    assert_eq!(
+        GameState::with_random(|rand| rand.gen(0, u64::MAX)),
+        expected
+    );
+}
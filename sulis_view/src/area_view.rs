@@ -1125,9 +1125,9 @@ impl WidgetKind for AreaView {
                         if w < 1.0 && h < 1.0 {
                             fire_action = true;
                         } else {
-                            GameState::select_party_members(
-                                self.select_party_in_box(&widget.borrow()),
-                            );
+                            let mut members = self.select_party_in_box(&widget.borrow());
+                            members.retain(|e| !GameState::is_party_ai_current(e));
+                            GameState::select_party_members(members);
                         }
                         self.selection_box_start = None;
                     } else {
@@ -323,6 +323,7 @@ impl BuilderSet for CharacterCreator {
             reward: None,
             abilities,
             ai: None,
+            schedule: None,
         };
 
         match write_character_to_file(&filename, &actor) {
@@ -258,6 +258,7 @@ fn export_character(pc: &ActorState) {
         xp: Some(pc.xp()),
         reward: None,
         ai: None,
+        schedule: None,
     };
 
     match write_character_to_file(&filename, &actor) {
@@ -0,0 +1,125 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use chrono::prelude::*;
+
+use sulis_core::config;
+use sulis_core::ui::{Callback, Widget, WidgetKind};
+use sulis_core::widgets::{Button, ScrollPane, TextArea};
+use sulis_state::combat_log::CombatLogKind;
+use sulis_state::{ChangeListener, CombatLog};
+
+use crate::RootView;
+
+pub const NAME: &str = "combat_log_window";
+
+/// The maximum number of entries shown in the window.  All retained
+/// entries are included when exporting.
+const MAX_DISPLAYED: usize = 200;
+
+pub struct CombatLogWindow {}
+
+impl CombatLogWindow {
+    pub fn new() -> Rc<RefCell<CombatLogWindow>> {
+        Rc::new(RefCell::new(CombatLogWindow {}))
+    }
+}
+
+fn kind_id(kind: &CombatLogKind) -> &'static str {
+    match kind {
+        CombatLogKind::Attack { .. } => "attack",
+        CombatLogKind::EffectApplied { .. } => "effect_applied",
+        CombatLogKind::EffectRemoved { .. } => "effect_removed",
+        CombatLogKind::AbilityUsed { .. } => "ability_used",
+        CombatLogKind::Death { .. } => "death",
+    }
+}
+
+fn export_path() -> PathBuf {
+    let utc = Utc::now();
+
+    let mut path = config::USER_DIR.clone();
+    path.push("combat_logs");
+    path.push(format!("combat_log_{}.txt", utc.format("%Y%m%d-%H%M%S")));
+    path
+}
+
+impl WidgetKind for CombatLogWindow {
+    widget_kind!(NAME);
+
+    fn on_remove(&mut self, _widget: &Rc<RefCell<Widget>>) {
+        CombatLog::remove_listener(NAME);
+    }
+
+    fn on_add(&mut self, widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
+        CombatLog::add_listener(ChangeListener::invalidate(NAME, widget));
+
+        let close = Widget::with_theme(Button::empty(), "close");
+        close
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, _) = Widget::parent::<CombatLogWindow>(widget);
+                parent.borrow_mut().mark_for_removal();
+            })));
+
+        let export = Widget::with_theme(Button::empty(), "export");
+        export
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let path = export_path();
+                let text = match CombatLog::export(&path) {
+                    Ok(()) => format!("Exported combat log to {}", path.to_string_lossy()),
+                    Err(e) => {
+                        warn!("Unable to export combat log to {:?}", path);
+                        warn!("{}", e);
+                        "Unable to export combat log.".to_string()
+                    }
+                };
+
+                let (_, root_view) = Widget::parent_mut::<RootView>(widget);
+                root_view.add_status_text(&text);
+            })));
+
+        let entries_pane = ScrollPane::new();
+        let entries_widget = Widget::with_theme(entries_pane.clone(), "entries");
+
+        let entries = CombatLog::entries();
+        for entry in entries.iter().rev().take(MAX_DISPLAYED) {
+            let text = Widget::with_theme(TextArea::empty(), "entry");
+            {
+                let state = &mut text.borrow_mut().state;
+                state.add_text_arg("time", &entry.time_text());
+                state.add_text_arg("text", &entry.kind.to_string());
+                state.add_text_arg(kind_id(&entry.kind), "true");
+            }
+            entries_pane.borrow().add_to_content(text);
+        }
+
+        if entries.is_empty() {
+            let empty = Widget::with_theme(TextArea::empty(), "empty");
+            entries_pane.borrow().add_to_content(empty);
+        }
+
+        vec![close, export, entries_widget]
+    }
+}
@@ -29,7 +29,7 @@ use sulis_module::{
 use sulis_state::{
     area_feedback_text::ColorKind,
     script::{entity_with_id, CallbackData, FuncKind, ScriptEntity},
-    ChangeListener, EntityState, GameState, ItemState, NextGameStep, Script,
+    ChangeListener, EntityState, GameState, ItemState, NewGameOptions, NextGameStep, Script,
 };
 
 use crate::{
@@ -64,6 +64,76 @@ impl DialogWindow {
             cur_node,
         }))
     }
+
+    /// Returns the ID of the conversation node currently being shown
+    pub fn cur_node(&self) -> &str {
+        &self.cur_node
+    }
+
+    /// Returns the index and text of each response to the current node
+    /// that is viewable by the player
+    pub fn viewable_responses(&self) -> Vec<(usize, String)> {
+        self.convo
+            .responses(&self.cur_node)
+            .iter()
+            .enumerate()
+            .filter(|(_, response)| is_viewable(response, &self.pc, &self.entity))
+            .map(|(index, response)| (index, response.text.to_string()))
+            .collect()
+    }
+
+    /// Activates the response at the specified index for the current node,
+    /// exactly as if the player had clicked on it.  `widget` must be the
+    /// widget owning this window
+    pub fn select_response(&mut self, widget: &Rc<RefCell<Widget>>, index: usize) {
+        let convo = Rc::clone(&self.convo);
+        let response = match convo.responses(&self.cur_node).get(index) {
+            None => {
+                warn!("Invalid response index {} for node '{}'", index, self.cur_node);
+                return;
+            }
+            Some(response) => response,
+        };
+
+        activate(widget, &response.on_select, &self.pc, &self.entity);
+
+        let (_, view) = Widget::parent_mut::<RootView>(widget);
+        let (area, _) = view.area_view();
+
+        match response.to {
+            None => {
+                widget.borrow_mut().mark_for_removal();
+                area.borrow_mut().set_active_entity(None);
+            }
+            Some(ref to) => {
+                self.check_switch_speaker(to, &area);
+                self.cur_node = to.to_string();
+                widget.borrow_mut().invalidate_children()
+            }
+        }
+    }
+
+    fn check_switch_speaker(&self, node: &str, area: &Rc<RefCell<AreaView>>) {
+        let speaker = match self.convo.switch_speaker(node) {
+            None => return,
+            Some(ref speaker) => speaker,
+        };
+
+        let speaker = match entity_with_id(speaker.to_string()) {
+            None => {
+                warn!("Attempted to switch to invalid speaker '{}'", speaker);
+                return;
+            }, Some(speaker) => speaker,
+        };
+
+        let (x, y) = {
+            let speaker = &speaker.borrow().location;
+            (speaker.x, speaker.y)
+        };
+        let cb = OnTrigger::ScrollView(x, y);
+        GameState::add_ui_callback(vec![cb], &self.pc, &speaker);
+        area.borrow_mut().set_active_entity(Some(Rc::clone(&speaker)));
+    }
 }
 
 impl WidgetKind for DialogWindow {
@@ -132,12 +202,12 @@ impl WidgetKind for DialogWindow {
 
         let responses_widget = Widget::empty("responses");
         {
-            for response in responses {
+            for (index, response) in responses.iter().enumerate() {
                 if !is_viewable(response, &self.pc, &self.entity) {
                     continue;
                 }
 
-                let response_button = ResponseButton::new(&self.convo, &response, &self.pc);
+                let response_button = ResponseButton::new(index, response, &self.pc);
                 let widget = Widget::with_defaults(response_button);
                 Widget::add_child_to(&responses_widget, widget);
             }
@@ -148,49 +218,23 @@ impl WidgetKind for DialogWindow {
 }
 
 struct ResponseButton {
+    index: usize,
     text: String,
-    to: Option<String>,
-    on_select: Vec<OnTrigger>,
     pc: Rc<RefCell<EntityState>>,
-    convo: Rc<Conversation>,
 }
 
 impl ResponseButton {
     fn new(
-        convo: &Rc<Conversation>,
+        index: usize,
         response: &Response,
         pc: &Rc<RefCell<EntityState>>,
     ) -> Rc<RefCell<ResponseButton>> {
         Rc::new(RefCell::new(ResponseButton {
+            index,
             text: response.text.to_string(),
-            to: response.to.clone(),
-            on_select: response.on_select.clone(),
             pc: Rc::clone(&pc),
-            convo: Rc::clone(convo),
         }))
     }
-
-    fn check_switch_speaker(&self, node: &str, area: &Rc<RefCell<AreaView>>) {
-        let speaker = match self.convo.switch_speaker(node) {
-            None => return,
-            Some(ref speaker) => speaker,
-        };
-
-        let speaker = match entity_with_id(speaker.to_string()) {
-            None => {
-                warn!("Attempted to switch to invalid speaker '{}'", speaker);
-                return;
-            }, Some(speaker) => speaker,
-        };
-
-        let (x, y) = {
-            let speaker = &speaker.borrow().location;
-            (speaker.x, speaker.y)
-        };
-        let cb = OnTrigger::ScrollView(x, y);
-        GameState::add_ui_callback(vec![cb], &self.pc, &speaker);
-        area.borrow_mut().set_active_entity(Some(Rc::clone(&speaker)));
-    }
 }
 
 impl WidgetKind for ResponseButton {
@@ -220,23 +264,7 @@ impl WidgetKind for ResponseButton {
         self.super_on_mouse_release(widget, kind);
 
         let (parent, window) = Widget::parent_mut::<DialogWindow>(widget);
-
-        activate(widget, &self.on_select, &window.pc, &window.entity);
-
-        let (_, view) = Widget::parent_mut::<RootView>(&parent);
-        let (area, _) = view.area_view();
-
-        match self.to {
-            None => {
-                parent.borrow_mut().mark_for_removal();
-                area.borrow_mut().set_active_entity(None);
-            }
-            Some(ref to) => {
-                self.check_switch_speaker(to, &area);
-                window.cur_node = to.to_string();
-                parent.borrow_mut().invalidate_children()
-            }
-        }
+        window.select_response(&parent, self.index);
 
         true
     }
@@ -623,6 +651,10 @@ fn load_module(widget: &Rc<RefCell<Widget>>, module_id: &str) {
         let step = NextGameStep::LoadModuleAndNewCampaign {
             pc_actor: Rc::new(actor),
             module_dir: module.dir.to_string(),
+            options: NewGameOptions {
+                ironman: GameState::is_ironman(),
+                difficulty: Some(GameState::difficulty().id.to_string()),
+            },
         };
         view.set_next_step(step);
 
@@ -20,6 +20,7 @@ use std::rc::Rc;
 
 use sulis_core::ui::{Callback, Widget, WidgetKind};
 use sulis_core::widgets::{Button, ConfirmationWindow};
+use sulis_module::Module;
 use sulis_state::GameState;
 
 use crate::{LoadWindow, RootView};
@@ -66,7 +67,7 @@ impl WidgetKind for InGameMenu {
             })));
         save.borrow_mut()
             .state
-            .set_enabled(!GameState::is_combat_active());
+            .set_enabled(!GameState::is_combat_active() && !GameState::is_ironman());
 
         let load = Widget::with_theme(Button::empty(), "load");
         load.borrow_mut()
@@ -80,6 +81,32 @@ impl WidgetKind for InGameMenu {
                 window.borrow_mut().state.set_modal(true);
                 Widget::add_child_to(&root, window);
             })));
+        load.borrow_mut()
+            .state
+            .set_enabled(!GameState::is_ironman());
+
+        let difficulty = Widget::with_theme(Button::empty(), "difficulty");
+        difficulty
+            .borrow_mut()
+            .state
+            .add_text_arg("difficulty", &GameState::difficulty().name);
+        difficulty
+            .borrow_mut()
+            .state
+            .set_enabled(!GameState::is_combat_active() && Module::rules().difficulties.len() > 1);
+        difficulty
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let next = next_difficulty(&GameState::difficulty().id);
+                GameState::set_difficulty(&next);
+
+                let mut widget = widget.borrow_mut();
+                widget
+                    .state
+                    .add_text_arg("difficulty", &GameState::difficulty().name);
+                widget.invalidate_layout();
+            })));
 
         let menu = Widget::with_theme(Button::empty(), "menu");
         let menu_cb = self.menu_callback.clone();
@@ -111,6 +138,21 @@ impl WidgetKind for InGameMenu {
                 Widget::add_child_to(&root, window);
             })));
 
-        vec![back, save, load, menu, exit]
+        vec![back, save, load, difficulty, menu, exit]
     }
 }
+
+/// Returns the ID of the difficulty after the specified one in the rules,
+/// wrapping around to the first
+pub(crate) fn next_difficulty(id: &str) -> String {
+    let difficulties = &Module::rules().difficulties;
+    let index = difficulties.iter().position(|d| d.id == id);
+    let next = match index {
+        None => 0,
+        Some(index) => (index + 1) % difficulties.len(),
+    };
+
+    difficulties
+        .get(next)
+        .map_or_else(String::new, |d| d.id.to_string())
+}
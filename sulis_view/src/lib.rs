@@ -49,13 +49,16 @@ pub use self::character_window::CharacterWindow;
 mod class_pane;
 pub use self::class_pane::ClassPane;
 
+mod combat_log_window;
+pub use self::combat_log_window::CombatLogWindow;
+
 mod console_window;
 pub use self::console_window::ConsoleWindow;
 
 mod cutscene_window;
 pub use self::cutscene_window::CutsceneWindow;
 
-mod dialog_window;
+pub mod dialog_window;
 pub use self::dialog_window::DialogWindow;
 
 mod formation_window;
@@ -91,6 +94,9 @@ pub use self::load_window::LoadWindow;
 mod merchant_window;
 pub use self::merchant_window::MerchantWindow;
 
+mod mod_mismatch_window;
+pub use self::mod_mismatch_window::ModMismatchWindow;
+
 mod portrait_view;
 pub use self::portrait_view::PortraitView;
 
@@ -123,14 +129,18 @@ use std::rc::Rc;
 use std::time::Instant;
 
 use sulis_core::config::Config;
-use sulis_core::io::{InputAction, MainLoopUpdater};
+use sulis_core::io::{InputAction, MainLoopUpdater, Thumbnail};
 use sulis_core::ui::{Callback, Cursor, Widget, WidgetKind};
 use sulis_core::util;
 use sulis_core::widgets::{Button, ConfirmationWindow, Label};
 use sulis_module::{area::OnRest, Module};
 use sulis_state::{
-    area_feedback_text::ColorKind, save_file::create_save, script::script_callback,
-    script::ScriptEntity, ChangeListener, EntityState, GameState, NextGameStep, Script,
+    area_feedback_text::ColorKind,
+    save_file::{self, create_save},
+    script::script_cache,
+    script::script_callback,
+    script::ScriptEntity,
+    ChangeListener, EntityState, GameState, NextGameStep, Script,
 };
 
 const NAME: &str = "game";
@@ -294,6 +304,15 @@ impl RootView {
         });
     }
 
+    pub fn set_combat_log_window(&mut self, widget: &Rc<RefCell<Widget>>, desired_state: bool) {
+        self.set_window(
+            widget,
+            self::combat_log_window::NAME,
+            desired_state,
+            &|| Some(CombatLogWindow::new()),
+        );
+    }
+
     pub fn set_formation_window(&mut self, widget: &Rc<RefCell<Widget>>, desired_state: bool) {
         self.set_window(widget, self::formation_window::NAME, desired_state, &|| {
             Some(FormationWindow::new())
@@ -362,6 +381,11 @@ impl RootView {
         self.set_quest_window(widget, desired_state);
     }
 
+    pub fn toggle_combat_log_window(&mut self, widget: &Rc<RefCell<Widget>>) {
+        let desired_state = !Widget::has_child_with_name(widget, self::combat_log_window::NAME);
+        self.set_combat_log_window(widget, desired_state);
+    }
+
     pub fn toggle_map_window(&mut self, widget: &Rc<RefCell<Widget>>) {
         let desired_state = !Widget::has_child_with_name(widget, self::world_map_window::NAME);
         self.set_map_window(widget, desired_state, false);
@@ -402,7 +426,28 @@ impl RootView {
         }
     }
 
+    fn reload_scripts(&mut self) {
+        let reloaded = script_cache::reload();
+        self.add_status_text(&format!("Reloaded {} scripts.", reloaded.len()));
+    }
+
+    fn reload_resources(&mut self) {
+        match GameState::reload_resources() {
+            Ok(count) => self.add_status_text(&format!("Reloaded {} resources.", count)),
+            Err(e) => {
+                error!("Error reloading resources");
+                error!("{}", e);
+                self.add_status_text("Error reloading resources!");
+            }
+        }
+    }
+
     pub fn save(&mut self) {
+        if GameState::is_ironman() {
+            self.add_status_text("Cannot save in Ironman mode.");
+            return;
+        }
+
         if GameState::is_combat_active() {
             self.add_status_text("Cannot save during combat.");
             return;
@@ -416,12 +461,33 @@ impl RootView {
             self.add_status_text("Save Complete.");
         }
     }
+
+    fn update_autosave(&mut self, millis: u32) {
+        match save_file::update_autosave(millis) {
+            None => (),
+            Some(Ok(())) => self.add_status_text("Autosave Complete."),
+            Some(Err(e)) => {
+                error!("Error autosaving game");
+                error!("{}", e);
+                self.add_status_text("Error performing Autosave!");
+            }
+        }
+    }
 }
 
 impl WidgetKind for RootView {
     widget_kind!(NAME);
 
     fn update(&mut self, widget: &Rc<RefCell<Widget>>, millis: u32) {
+        {
+            let area_view = self.area_view_widget.borrow();
+            let region = (
+                area_view.state.inner_position(),
+                area_view.state.inner_size(),
+            );
+            Thumbnail::set_region(Some(region));
+        }
+
         let area_state = GameState::area_state();
         let root = Widget::get_root(widget);
         let area = area_state.borrow().area.area.id.clone();
@@ -438,6 +504,8 @@ impl WidgetKind for RootView {
             }
         }
 
+        self.update_autosave(millis);
+
         let root = Widget::get_root(widget);
         let has_modal = root.borrow().has_modal();
         GameState::set_modal_locked(has_modal);
@@ -499,10 +567,13 @@ impl WidgetKind for RootView {
             ToggleMap => self.toggle_map_window(widget),
             ToggleJournal => self.toggle_quest_window(widget),
             ToggleFormation => self.toggle_formation_window(widget),
+            ToggleCombatLog => self.toggle_combat_log_window(widget),
             EndTurn => self.end_turn(),
             Exit => self.show_exit(widget),
-            SelectAll => GameState::select_party_members(GameState::party()),
+            SelectAll => select_all_party_members(),
             QuickSave => self.save(),
+            ReloadScripts if Config::debug().hot_reload => self.reload_scripts(),
+            ReloadResources if Config::debug().hot_reload => self.reload_resources(),
             ScrollUp => self.area_view.borrow_mut().scroll(0.0, 2.0, 33),
             ScrollDown => self.area_view.borrow_mut().scroll(0.0, -2.0, 33),
             ScrollRight => self.area_view.borrow_mut().scroll(-2.0, 0.0, 33),
@@ -539,7 +610,7 @@ impl WidgetKind for RootView {
                 .borrow_mut()
                 .state
                 .add_callback(Callback::new(Rc::new(|_, _| {
-                    GameState::select_party_members(GameState::party());
+                    select_all_party_members();
                 })));
 
             let rest = Widget::with_theme(Button::empty(), "rest_button");
@@ -586,7 +657,7 @@ impl WidgetKind for RootView {
                 Box::new(move |timer| {
                     let enabled = match timer.current() {
                         None => false,
-                        Some(entity) => entity.borrow().is_party_member(),
+                        Some(entity) => entity.borrow().is_player_controlled(),
                     };
                     end_turn_button_ref.borrow_mut().state.set_enabled(enabled);
                 }),
@@ -628,6 +699,15 @@ impl WidgetKind for RootView {
                     view.toggle_quest_window(&root);
                 })));
 
+            let combat_log_button = Widget::with_theme(Button::empty(), "combat_log_button");
+            combat_log_button
+                .borrow_mut()
+                .state
+                .add_callback(Callback::new(Rc::new(|widget, _| {
+                    let (root, view) = Widget::parent_mut::<RootView>(widget);
+                    view.toggle_combat_log_window(&root);
+                })));
+
             let men_button = Widget::with_theme(Button::empty(), "menu_button");
             men_button
                 .borrow_mut()
@@ -645,6 +725,7 @@ impl WidgetKind for RootView {
                     cha_button,
                     map_button,
                     log_button,
+                    combat_log_button,
                     men_button,
                 ],
             );
@@ -720,6 +801,13 @@ impl WidgetKind for RootView {
                 // this prevents this callback from being called over and over
                 party[0].borrow_mut().actor.set_disabled(true);
 
+                if GameState::is_ironman() {
+                    if let Err(e) = save_file::delete_ironman_save() {
+                        error!("Error deleting ironman save");
+                        error!("{}", e);
+                    }
+                }
+
                 let menu_cb = Callback::new(Rc::new(|widget, _| {
                     let (_, view) = Widget::parent_mut::<RootView>(widget);
                     view.next_step = Some(NextGameStep::MainMenu);
@@ -745,6 +833,12 @@ impl WidgetKind for RootView {
     }
 }
 
+fn select_all_party_members() {
+    let mut members = GameState::party();
+    members.retain(|e| !GameState::is_party_ai_current(e));
+    GameState::select_party_members(members);
+}
+
 fn is_defeated(party: &[Rc<RefCell<EntityState>>]) -> bool {
     if party.len() == 0 {
         return true;
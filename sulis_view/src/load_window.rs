@@ -18,12 +18,16 @@ use std::any::Any;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use sulis_core::extern_image::{self, ImageBuffer, Rgba};
+use sulis_core::io::{DrawList, GraphicsRenderer, TextureMagFilter, TextureMinFilter};
 use sulis_core::ui::{Callback, Widget, WidgetKind};
+use sulis_core::util::Point;
 use sulis_core::widgets::{Button, ConfirmationWindow, Label, ScrollPane, TextArea};
+use sulis_module::Module;
 use sulis_state::save_file::{delete_save, get_available_save_files, load_state};
 use sulis_state::{NextGameStep, SaveFileMetaData, SaveState};
 
-use crate::{main_menu::MainMenu, LoadingScreen, RootView};
+use crate::{main_menu::MainMenu, LoadingScreen, ModMismatchWindow, RootView};
 
 const NAME: &str = "load_window";
 
@@ -31,6 +35,8 @@ pub struct LoadWindow {
     accept: Rc<RefCell<Widget>>,
     delete: Rc<RefCell<Widget>>,
     pub(crate) cancel: Rc<RefCell<Widget>>,
+    details: Rc<RefCell<SaveDetails>>,
+    details_widget: Rc<RefCell<Widget>>,
     entries: Vec<SaveFileMetaData>,
     selected_entry: Option<usize>,
     main_menu_mode: bool,
@@ -50,10 +56,15 @@ impl LoadWindow {
             }
         };
 
+        let details = Rc::new(RefCell::new(SaveDetails { meta: None }));
+        let details_widget = Widget::with_theme(details.clone(), "details");
+
         Rc::new(RefCell::new(LoadWindow {
             accept,
             delete,
             cancel,
+            details,
+            details_widget,
             entries,
             selected_entry: None,
             main_menu_mode,
@@ -66,30 +77,31 @@ impl LoadWindow {
             Some(index) => index,
         };
 
-        match load_state(&self.entries[index]) {
-            Err(e) => {
-                error!("Error reading game state");
-                error!("{}", e);
-            }
-            Ok(state) => {
-                self.set_load_step(state, root);
-            }
+        let meta = &self.entries[index];
+        if let Some(mismatch) = meta.mod_mismatch() {
+            warn!(
+                "Save '{}' was made with different mods than are currently active",
+                meta.player_name
+            );
+            let window = Widget::with_defaults(ModMismatchWindow::new(
+                meta.clone(),
+                mismatch,
+                self.main_menu_mode,
+            ));
+            window.borrow_mut().state.set_modal(true);
+            Widget::add_child_to(root, window);
+            return;
         }
+
+        load_save(meta, self.main_menu_mode, root);
     }
 
     pub fn set_load_step(&self, save_state: SaveState, root: &Rc<RefCell<Widget>>) {
-        // TODO remove the bool flag passed in the constructor
-        if self.main_menu_mode {
-            let main_menu = Widget::kind_mut::<MainMenu>(root);
-            main_menu.next_step = Some(NextGameStep::LoadCampaign { save_state });
-        } else {
-            let root_view = Widget::kind_mut::<RootView>(root);
-            root_view.next_step = Some(NextGameStep::LoadCampaign { save_state });
-        }
-
-        let loading_screen = Widget::with_defaults(LoadingScreen::new());
-        loading_screen.borrow_mut().state.set_modal(true);
-        Widget::add_child_to(&root, loading_screen);
+        set_next_step(
+            NextGameStep::LoadCampaign { save_state },
+            self.main_menu_mode,
+            root,
+        );
     }
 
     pub fn delete_save(&mut self) {
@@ -109,6 +121,12 @@ impl LoadWindow {
         self.entries.remove(index);
     }
 
+    fn show_details(&self) {
+        let meta = self.selected_entry.map(|index| self.entries[index].clone());
+        self.details.borrow_mut().meta = meta;
+        self.details_widget.borrow_mut().invalidate_children();
+    }
+
     fn set_button_state(&self) {
         self.delete
             .borrow_mut()
@@ -124,6 +142,40 @@ impl LoadWindow {
     }
 }
 
+/// Reads the specified save file and, if successful, sets it to be loaded
+pub(crate) fn load_save(meta: &SaveFileMetaData, main_menu_mode: bool, root: &Rc<RefCell<Widget>>) {
+    match load_state(meta) {
+        Err(e) => {
+            error!("Error reading game state");
+            error!("{}", e);
+        }
+        Ok(save_state) => {
+            set_next_step(
+                NextGameStep::LoadCampaign { save_state },
+                main_menu_mode,
+                root,
+            );
+        }
+    }
+}
+
+/// Sets the next step on the main menu or root view, and shows the loading
+/// screen
+pub(crate) fn set_next_step(step: NextGameStep, main_menu_mode: bool, root: &Rc<RefCell<Widget>>) {
+    // TODO remove the bool flag passed in the constructor
+    if main_menu_mode {
+        let main_menu = Widget::kind_mut::<MainMenu>(root);
+        main_menu.next_step = Some(step);
+    } else {
+        let root_view = Widget::kind_mut::<RootView>(root);
+        root_view.next_step = Some(step);
+    }
+
+    let loading_screen = Widget::with_defaults(LoadingScreen::new());
+    loading_screen.borrow_mut().state.set_modal(true);
+    Widget::add_child_to(root, loading_screen);
+}
+
 impl WidgetKind for LoadWindow {
     widget_kind!(NAME);
 
@@ -146,6 +198,7 @@ impl WidgetKind for LoadWindow {
             load_window.delete_save();
             load_window.selected_entry = None;
             load_window.set_button_state();
+            load_window.show_details();
 
             let (parent, _) = Widget::parent::<ConfirmationWindow>(widget);
             parent.borrow_mut().mark_for_removal();
@@ -192,6 +245,18 @@ impl WidgetKind for LoadWindow {
                 .borrow_mut()
                 .state
                 .add_text_arg("current_area_name", &meta.current_area_name);
+            if meta.autosave {
+                text_area
+                    .borrow_mut()
+                    .state
+                    .add_text_arg("autosave", "Autosave");
+            }
+            if meta.ironman {
+                text_area
+                    .borrow_mut()
+                    .state
+                    .add_text_arg("ironman", "Ironman");
+            }
             if meta.error.is_some() {
                 text_area
                     .borrow_mut()
@@ -208,6 +273,7 @@ impl WidgetKind for LoadWindow {
                     parent.borrow_mut().invalidate_layout();
                     load_window.selected_entry = Some(index);
                     load_window.set_button_state();
+                    load_window.show_details();
 
                     let content = Widget::direct_parent(widget);
                     for child in content.borrow().children.iter() {
@@ -227,6 +293,7 @@ impl WidgetKind for LoadWindow {
         }
 
         self.set_button_state();
+        self.show_details();
 
         vec![
             self.cancel.clone(),
@@ -234,6 +301,128 @@ impl WidgetKind for LoadWindow {
             self.accept.clone(),
             title,
             entries,
+            self.details_widget.clone(),
         ]
     }
 }
+
+/// Shows the thumbnail and meta data of the currently selected save
+struct SaveDetails {
+    meta: Option<SaveFileMetaData>,
+}
+
+impl WidgetKind for SaveDetails {
+    widget_kind!("save_details");
+
+    fn on_add(&mut self, _widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
+        let meta = match self.meta {
+            None => return Vec::new(),
+            Some(ref meta) => meta,
+        };
+
+        let mut children = Vec::new();
+        if let Some(path) = meta.thumbnail_path() {
+            match extern_image::open(&path) {
+                Ok(image) => {
+                    let thumbnail = SaveThumbnail {
+                        image: Some(image.to_rgba()),
+                    };
+                    children.push(Widget::with_theme(
+                        Rc::new(RefCell::new(thumbnail)),
+                        "thumbnail",
+                    ));
+                }
+                Err(e) => {
+                    warn!("Unable to read save thumbnail from {:?}", path);
+                    warn!("{}", e);
+                }
+            }
+        }
+
+        let text_area = Widget::with_theme(TextArea::empty(), "text_area");
+        {
+            let state = &mut text_area.borrow_mut().state;
+            state.add_text_arg("campaign_name", &meta.campaign_name);
+
+            if !meta.mods.is_empty() {
+                let mods: Vec<&str> = meta.mods.iter().map(|m| m.name.as_str()).collect();
+                state.add_text_arg("mods", &mods.join(", "));
+            }
+
+            let party: Vec<String> = meta
+                .party
+                .iter()
+                .map(|member| {
+                    let classes: Vec<String> = member
+                        .classes
+                        .iter()
+                        .map(|(class, level)| format!("{} {}", class, level))
+                        .collect();
+                    format!("{} ({})", member.name, classes.join(" / "))
+                })
+                .collect();
+            if !party.is_empty() {
+                state.add_text_arg("party", &party.join("\n"));
+            }
+
+            if let Some(time) = meta.time {
+                let rules = Module::rules();
+                if (time.hour as usize) < rules.hour_names.len() {
+                    state.add_text_arg("day", &time.day.to_string());
+                    state.add_text_arg("hour", rules.get_hour_name(time.hour));
+                }
+            }
+
+            if meta.play_time_millis > 0 {
+                let minutes = meta.play_time_millis / 60_000;
+                let play_time = format!("{}:{:02}", minutes / 60, minutes % 60);
+                state.add_text_arg("play_time", &play_time);
+            }
+        }
+        children.push(text_area);
+
+        children
+    }
+}
+
+const THUMBNAIL_TEXTURE_ID: &str = "__save_thumbnail__";
+const THUMBNAIL_TEX_COORDS: [f32; 8] = [0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0];
+
+/// Draws a save's thumbnail image.  The image is registered as a texture
+/// when first drawn, replacing the previously shown thumbnail.
+struct SaveThumbnail {
+    image: Option<ImageBuffer<Rgba<u8>, Vec<u8>>>,
+}
+
+impl WidgetKind for SaveThumbnail {
+    widget_kind!("save_thumbnail");
+
+    fn draw(
+        &mut self,
+        renderer: &mut dyn GraphicsRenderer,
+        _pixel_size: Point,
+        widget: &Widget,
+        _millis: u32,
+    ) {
+        if let Some(image) = self.image.take() {
+            renderer.register_texture(
+                THUMBNAIL_TEXTURE_ID,
+                image,
+                TextureMinFilter::Linear,
+                TextureMagFilter::Linear,
+            );
+        }
+
+        let pos = widget.state.inner_position();
+        let size = widget.state.inner_size();
+        let draw_list = DrawList::from_texture_id(
+            THUMBNAIL_TEXTURE_ID,
+            &THUMBNAIL_TEX_COORDS,
+            pos.x as f32,
+            pos.y as f32,
+            size.width as f32,
+            size.height as f32,
+        );
+        renderer.draw(draw_list);
+    }
+}
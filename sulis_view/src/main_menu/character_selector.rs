@@ -21,9 +21,10 @@ use std::rc::Rc;
 use sulis_core::ui::*;
 use sulis_core::widgets::{Button, ConfirmationWindow, Label, ScrollPane, TextArea};
 use sulis_module::{Actor, Module};
-use sulis_state::{ActorState, NextGameStep};
+use sulis_state::{ActorState, NewGameOptions, NextGameStep};
 
 use crate::character_window::create_details_text_box;
+use crate::in_game_menu::next_difficulty;
 use crate::{main_menu::MainMenu, CharacterBuilder, LoadingScreen};
 
 pub struct CharacterSelector {
@@ -31,6 +32,7 @@ pub struct CharacterSelector {
     first_add: bool,
     main_menu: Rc<RefCell<Widget>>,
     to_select: Option<String>,
+    options: NewGameOptions,
 }
 
 impl CharacterSelector {
@@ -40,6 +42,7 @@ impl CharacterSelector {
             first_add: true,
             main_menu,
             to_select: None,
+            options: NewGameOptions::default(),
         }))
     }
 
@@ -190,14 +193,55 @@ impl WidgetKind for CharacterSelector {
                     Some(ref selected) => Rc::clone(selected),
                 };
 
+                let options = selector.options.clone();
                 let (root, window) = Widget::parent_mut::<MainMenu>(&parent);
-                window.next_step = Some(NextGameStep::NewCampaign { pc_actor: selected });
+                window.next_step = Some(NextGameStep::NewCampaign {
+                    pc_actor: selected,
+                    options,
+                });
 
                 let loading_screen = Widget::with_defaults(LoadingScreen::new());
                 loading_screen.borrow_mut().state.set_modal(true);
                 Widget::add_child_to(&root, loading_screen);
             })));
 
+        let ironman_button = Widget::with_theme(Button::empty(), "ironman_button");
+        ironman_button
+            .borrow_mut()
+            .state
+            .set_active(self.options.ironman);
+        ironman_button
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (_, selector) = Widget::parent_mut::<CharacterSelector>(widget);
+                selector.options.ironman = !selector.options.ironman;
+                widget
+                    .borrow_mut()
+                    .state
+                    .set_active(selector.options.ironman);
+            })));
+
+        let difficulty = Module::rules().difficulty(self.options.difficulty.as_deref());
+        let difficulty_button = Widget::with_theme(Button::empty(), "difficulty_button");
+        difficulty_button
+            .borrow_mut()
+            .state
+            .add_text_arg("difficulty", &difficulty.name);
+        difficulty_button
+            .borrow_mut()
+            .state
+            .set_enabled(Module::rules().difficulties.len() > 1);
+        difficulty_button
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(move |widget, _| {
+                let (parent, selector) = Widget::parent_mut::<CharacterSelector>(widget);
+                let next = next_difficulty(&difficulty.id);
+                selector.options.difficulty = Some(next);
+                parent.borrow_mut().invalidate_children();
+            })));
+
         let details = if let Some(ref actor) = self.selected {
             let mut actor_state = ActorState::new(Rc::clone(actor));
             actor_state.compute_stats();
@@ -227,6 +271,8 @@ impl WidgetKind for CharacterSelector {
             new_character_button,
             delete_char_button,
             play_button,
+            ironman_button,
+            difficulty_button,
             details,
             invalid_level,
         ]
@@ -0,0 +1,117 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2018 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sulis_core::ui::{Callback, Widget, WidgetKind};
+use sulis_core::widgets::{Button, Label, TextArea};
+use sulis_state::save_file::{ModMetaData, ModMismatch};
+use sulis_state::{NextGameStep, SaveFileMetaData};
+
+use crate::load_window::{load_save, set_next_step};
+
+const NAME: &str = "mod_mismatch_window";
+
+/// Shown when loading a save that was made with a different set of mods
+/// than is currently active.  Offers to switch to the save's mods before
+/// loading, or to load it with the current mods.
+pub struct ModMismatchWindow {
+    meta: SaveFileMetaData,
+    mismatch: ModMismatch,
+    main_menu_mode: bool,
+}
+
+impl ModMismatchWindow {
+    pub fn new(
+        meta: SaveFileMetaData,
+        mismatch: ModMismatch,
+        main_menu_mode: bool,
+    ) -> Rc<RefCell<ModMismatchWindow>> {
+        Rc::new(RefCell::new(ModMismatchWindow {
+            meta,
+            mismatch,
+            main_menu_mode,
+        }))
+    }
+}
+
+fn mod_names(mods: &[ModMetaData]) -> String {
+    if mods.is_empty() {
+        return "None".to_string();
+    }
+
+    mods.iter()
+        .map(|m| m.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl WidgetKind for ModMismatchWindow {
+    widget_kind!(NAME);
+
+    fn on_add(&mut self, _widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
+        let title = Widget::with_theme(Label::empty(), "title");
+
+        let content = Widget::with_theme(TextArea::empty(), "content");
+        {
+            let state = &mut content.borrow_mut().state;
+            state.add_text_arg("saved_mods", &mod_names(&self.mismatch.saved));
+            state.add_text_arg("active_mods", &mod_names(&self.mismatch.active));
+            if !self.mismatch.unavailable.is_empty() {
+                state.add_text_arg("unavailable", &mod_names(&self.mismatch.unavailable));
+            }
+        }
+
+        let cancel = Widget::with_theme(Button::empty(), "cancel");
+        cancel
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, _) = Widget::parent::<ModMismatchWindow>(widget);
+                parent.borrow_mut().mark_for_removal();
+            })));
+
+        let load_anyway = Widget::with_theme(Button::empty(), "load_anyway");
+        load_anyway
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, window) = Widget::parent::<ModMismatchWindow>(widget);
+                let root = Widget::get_root(&parent);
+                load_save(&window.meta, window.main_menu_mode, &root);
+                parent.borrow_mut().mark_for_removal();
+            })));
+
+        let switch = Widget::with_theme(Button::empty(), "switch");
+        switch
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, window) = Widget::parent::<ModMismatchWindow>(widget);
+                let root = Widget::get_root(&parent);
+                let step = NextGameStep::LoadResourcesAndCampaign {
+                    resources: window.meta.active_resources(),
+                    save_file: window.meta.clone(),
+                };
+                set_next_step(step, window.main_menu_mode, &root);
+                parent.borrow_mut().mark_for_removal();
+            })));
+
+        vec![title, content, cancel, load_anyway, switch]
+    }
+}
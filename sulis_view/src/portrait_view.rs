@@ -21,6 +21,7 @@ use std::rc::Rc;
 use sulis_core::io::event;
 use sulis_core::ui::{Callback, Widget, WidgetKind};
 use sulis_core::widgets::{Button, Label, ProgressBar};
+use sulis_module::PartyAIPreset;
 use sulis_state::{ChangeListener, EntityState, GameState};
 
 use crate::CharacterBuilder;
@@ -46,6 +47,9 @@ impl WidgetKind for PortraitView {
             .actor
             .listeners
             .add(ChangeListener::invalidate(NAME, widget));
+        entity
+            .listeners
+            .add(ChangeListener::invalidate(NAME, widget));
 
         let portrait = Widget::with_theme(Label::empty(), "portrait");
         if let Some(ref image) = entity.actor.actor.portrait {
@@ -109,6 +113,22 @@ impl WidgetKind for PortraitView {
             .state
             .set_enabled(!GameState::is_combat_active());
 
+        let entity_ref = Rc::clone(&self.entity);
+        let party_ai = Widget::with_theme(Button::empty(), "party_ai");
+        {
+            let (short, name) = match entity.party_ai() {
+                None => ("-", "Manual"),
+                Some(preset) => (&preset.name()[0..1], preset.name()),
+            };
+            let state = &mut party_ai.borrow_mut().state;
+            state.add_text_arg("ai_short", short);
+            state.add_text_arg("ai_name", name);
+            state.add_callback(Callback::new(Rc::new(move |_, _| {
+                let preset = PartyAIPreset::cycle(entity_ref.borrow().party_ai());
+                entity_ref.borrow_mut().set_party_ai(preset);
+            })));
+        }
+
         widget
             .borrow_mut()
             .state
@@ -140,7 +160,7 @@ impl WidgetKind for PortraitView {
             Widget::add_child_to(&icons, icon_widget);
         }
 
-        vec![portrait, hp_bar, class_stat_bar, level_up, icons]
+        vec![portrait, hp_bar, class_stat_bar, level_up, party_ai, icons]
     }
 
     fn on_mouse_enter(&mut self, widget: &Rc<RefCell<Widget>>) -> bool {
@@ -168,7 +188,7 @@ impl WidgetKind for PortraitView {
         if let Some(targeter) = targeter {
             let mut targeter = targeter.borrow_mut();
             targeter.on_activate();
-        } else {
+        } else if !GameState::is_party_ai_current(&self.entity) {
             GameState::set_selected_party_member(Rc::clone(&self.entity));
         }
 